@@ -0,0 +1,306 @@
+use std::str::FromStr;
+use std::collections::{HashMap, HashSet};
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::map::Map;
+use crate::entity::Movement;
+use crate::position::Point;
+use crate::topology::Topology;
+use crate::config::SimulationConfig;
+
+/// Boundary enum
+///
+/// What happens to a move that would leave the board
+///
+/// * `Blocked` - The entity stays in its cell
+/// * `Wrap` - The top and bottom rows are joined, the entity appears
+///   on the other side (the left and right edges stay blocked). A hex
+///   board needs an even number of rows so the joined rows are neighbours
+/// * `Reflect` - The move bounces, the entity makes the mirrored move
+///   of its topology on the axis of the crossed edge
+/// * `Lethal` - The entity falls off the board and dies
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Boundary {
+    #[default]
+    Blocked,
+    Wrap,
+    Reflect,
+    Lethal,
+}
+
+impl FromStr for Boundary {
+
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "blocked" => Ok(Boundary::Blocked),
+            "wrap" => Ok(Boundary::Wrap),
+            "reflect" => Ok(Boundary::Reflect),
+            "lethal" => Ok(Boundary::Lethal),
+            _ => Err(format!("unknown boundary '{}', expected blocked, wrap, reflect or lethal", s)),
+        }
+    }
+}
+
+/// Goal struct
+///
+/// Region of the board the entities must reach
+///
+/// # Attributes
+///
+/// * `cells` - Cells of the region
+/// * `area` - Rectangle of cells added to the region, given by two opposite corners
+/// * `weight` - Reward of the region, the fitness of an entity is
+///   multiplied by it when it arrives
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Goal {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cells: Vec<Point>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub area: Option<[Point; 2]>,
+    pub weight: f64,
+}
+
+impl Default for Goal {
+
+    fn default() -> Self {
+        Goal { cells: Vec::new(), area: None, weight: 1.0 }
+    }
+}
+
+impl Goal {
+
+    /// Every cell of the region, the ones of `cells` followed by the ones of `area`
+
+    pub fn points(&self) -> Vec<Point> {
+
+        let mut points = self.cells.clone();
+
+        if let Some([a, b]) = self.area {
+            for y in a.y.min(b.y)..=a.y.max(b.y) {
+                for x in a.x.min(b.x)..=a.x.max(b.x) {
+                    points.push(Point::new(x, y));
+                }
+            }
+        }
+
+        points
+    }
+}
+
+/// Board struct
+///
+/// Layout of the cells where the entities move, built from the map of
+/// the configuration. Without a map entities start on the first two
+/// columns and reach the goal on the last one, the goals of the
+/// configuration replace the ones of the map
+///
+/// # Attributes
+///
+/// * `dimensions` - Board dimensions (rows, columns)
+/// * `boundary` - Rule of the moves that leave the board
+/// * `topology` - Moves of the entities
+/// * `walls` - Impassable cells
+/// * `starts` - Free cells where the entities are placed
+/// * `goals` - Free cells the entities must reach, with the index of their goal
+/// * `weights` - Reward of every goal
+/// * `start_bounds` - Corners of the smallest rectangle with every start cell
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Board {
+    pub dimensions: (usize, usize),
+    pub boundary: Boundary,
+    pub topology: Topology,
+    walls: HashSet<Point>,
+    starts: HashSet<Point>,
+    goals: HashMap<Point, usize>,
+    weights: Vec<f64>,
+    start_bounds: (Point, Point),
+}
+
+impl Board {
+
+    /// Create the board of a configuration, it must be valid
+    /// (`SimulationConfig::validate`)
+
+    pub fn new(config: &SimulationConfig) -> Self {
+
+        let map = match config.map.as_deref() {
+            Some(text) => Map::parse(text).expect("the map is checked by SimulationConfig::validate"),
+            None => Map::open(config.dimensions),
+        };
+
+        let walls: HashSet<Point> = map.walls.into_iter().chain(config.walls.iter().copied()).collect();
+
+        let free = |cells: Vec<Point>| -> HashSet<Point> {
+            cells.into_iter().filter(|p| !walls.contains(p)).collect()
+        };
+
+        let starts = free(map.starts);
+
+        let regions = match config.goals.is_empty() {
+            true => vec![Goal { cells: map.goals, ..Goal::default() }],
+            false => config.goals.clone(),
+        };
+
+        // Una celda que pertenece a varias metas cuenta para la primera
+
+        let mut goals = HashMap::new();
+
+        for (index, goal) in regions.iter().enumerate() {
+            for point in free(goal.points()) {
+                goals.entry(point).or_insert(index);
+            }
+        }
+
+        let weights = regions.iter().map(|g| g.weight).collect();
+
+        let min = |axis: fn(&Point) -> isize| starts.iter().map(axis).min().unwrap_or(0);
+        let max = |axis: fn(&Point) -> isize| starts.iter().map(axis).max().unwrap_or(0);
+
+        let start_bounds = (
+            Point::new(min(|p| p.x), min(|p| p.y)),
+            Point::new(max(|p| p.x), max(|p| p.y)),
+        );
+
+        Board {
+            dimensions: map.dimensions,
+            boundary: config.boundary,
+            topology: config.topology,
+            walls,
+            starts,
+            goals,
+            weights,
+            start_bounds,
+        }
+    }
+
+    /// Check if a point is inside the board
+
+    pub fn contains(&self, point: Point) -> bool {
+        let (rows, cols) = self.dimensions;
+        point.x >= 0 && point.y >= 0 && point.x < cols as isize && point.y < rows as isize
+    }
+
+    pub fn is_wall(&self, point: Point) -> bool {
+        self.walls.contains(&point)
+    }
+
+    /// Check if an entity can stand on a point
+
+    pub fn is_free(&self, point: Point) -> bool {
+        self.contains(point) && !self.is_wall(point)
+    }
+
+    pub fn is_start(&self, point: Point) -> bool {
+        self.starts.contains(&point)
+    }
+
+    pub fn is_goal(&self, point: Point) -> bool {
+        self.goals.contains_key(&point)
+    }
+
+    /// Get the index of the goal a point belongs to
+
+    pub fn goal(&self, point: Point) -> Option<usize> {
+        self.goals.get(&point).copied()
+    }
+
+    /// Get the reward of the goal a point belongs to
+
+    pub fn goal_weight(&self, point: Point) -> Option<f64> {
+        self.goal(point).map(|index| self.weights[index])
+    }
+
+    /// Cell reached by a move, with the boundary rule applied
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - Current cell of the entity
+    /// * `index` - Index of the move in the topology (`Topology::names`)
+    ///
+    /// # Returns
+    ///
+    /// * `Movement` - The reached cell, Blocked if it's a wall or the
+    ///   board edge blocks the move and Fall on a lethal edge
+
+    pub fn destination(&self, from: Point, index: usize) -> Movement {
+
+        let (rows, cols) = self.dimensions;
+        let next = from + self.topology.direction(from, index);
+
+        let next = match self.contains(next) {
+            true => next,
+            false => match self.boundary {
+                Boundary::Blocked => return Movement::Blocked,
+                Boundary::Lethal => return Movement::Fall,
+                Boundary::Wrap => Point::new(next.x, next.y.rem_euclid(rows as isize)),
+                Boundary::Reflect => {
+
+                    // Se usa el movimiento espejo de la topología en el eje del borde
+                    // cruzado, así en un tablero hexagonal sigue siendo un vecino
+
+                    let flip_x = !(0..cols as isize).contains(&next.x);
+                    let flip_y = !(0..rows as isize).contains(&next.y);
+
+                    from + self.topology.direction(from, self.topology.mirror(index, flip_x, flip_y))
+                },
+            },
+        };
+
+        match self.is_free(next) {
+            true => Movement::To(next),
+            false => Movement::Blocked,
+        }
+    }
+
+    /// Check if a row is drawn half a cell to the right, the odd rows of a hex board
+
+    pub fn is_shifted(&self, row: usize) -> bool {
+        self.topology == Topology::Hex && row % 2 == 1
+    }
+
+    /// Number of cells where entities can start
+
+    pub fn start_capacity(&self) -> usize {
+        self.starts.len()
+    }
+
+    /// Number of goal cells
+
+    pub fn goal_capacity(&self) -> usize {
+        self.goals.len()
+    }
+
+    /// Draw a random start cell, it can be occupied by another entity
+    ///
+    /// A row and then a column are drawn inside the rectangle of the
+    /// start cells until one of them is hit
+
+    pub fn random_start<R: Rng>(&self, rng: &mut R) -> Point {
+
+        let (min, max) = self.start_bounds;
+
+        loop {
+
+            // La columna se sortea como i32 para mantener la secuencia
+            // aleatoria de las semillas anteriores a los mapas
+
+            let y = rng.gen_range(min.y as usize..=max.y as usize) as isize;
+            let x = rng.gen_range(min.x as i32..=max.x as i32) as isize;
+
+            let point = Point::new(x, y);
+
+            if self.is_start(point) {
+                return point
+            }
+        }
+    }
+}
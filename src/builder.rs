@@ -0,0 +1,185 @@
+use crate::genome::Genome;
+use crate::board::{Boundary, Goal};
+use crate::topology::Topology;
+use crate::position::Point;
+use crate::poblation::Poblation;
+use crate::random::{self, SimRng};
+use crate::config::{ConfigError, SimulationConfig};
+
+/// SimulationBuilder struct
+/// 
+/// Builds a validated Poblation starting from a
+/// SimulationConfig (the default one if not given)
+/// 
+/// # Examples
+/// 
+/// ```
+/// use genetic::SimulationBuilder;
+/// 
+/// let mut poblation = SimulationBuilder::new()
+///     .seed(42)
+///     .dimensions(6, 10)
+///     .sample(8)
+///     .generations(5)
+///     .headless(true)
+///     .build()
+///     .unwrap();
+/// 
+/// poblation.step_generation();
+/// 
+/// assert_eq!(poblation.statistics("winners").unwrap().len(), 1);
+/// ```
+
+#[derive(Clone, Debug, Default)]
+pub struct SimulationBuilder {
+    config: SimulationConfig,
+    rng: Option<SimRng>,
+}
+
+impl SimulationBuilder {
+
+    pub fn new() -> Self {
+        SimulationBuilder::default()
+    }
+
+    /// Replace every parameter with the given config
+
+    pub fn config(mut self, config: SimulationConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.config.seed = Some(seed);
+        self
+    }
+
+    /// Use an already created generator instead of seeding one,
+    /// the recorded seed is left untouched
+
+    pub fn rng(mut self, rng: SimRng) -> Self {
+        self.rng = Some(rng);
+        self
+    }
+
+    /// Start from the given genomes instead of random entities
+
+    pub fn genomes(mut self, genomes: Vec<Genome>) -> Self {
+        self.config.genomes = genomes;
+        self
+    }
+
+    pub fn dimensions(mut self, rows: usize, cols: usize) -> Self {
+        self.config.dimensions = (rows, cols);
+        self
+    }
+
+    pub fn sample(mut self, sample: usize) -> Self {
+        self.config.sample = sample;
+        self
+    }
+
+    pub fn generations(mut self, generations: usize) -> Self {
+        self.config.n_generations = generations;
+        self
+    }
+
+    pub fn iterations(mut self, iterations: usize) -> Self {
+        self.config.n_iterations = iterations;
+        self
+    }
+
+    pub fn mutation_probability(mut self, probability: f64) -> Self {
+        self.config.mutation_probability = probability;
+        self
+    }
+
+    pub fn killer_probability(mut self, probability: f64) -> Self {
+        self.config.killer_probability = probability;
+        self
+    }
+
+    pub fn p(mut self, p: f64) -> Self {
+        self.config.p = p;
+        self
+    }
+
+    pub fn show_threshold(mut self, threshold: usize) -> Self {
+        self.config.show_threshold = threshold;
+        self
+    }
+
+    pub fn frame_delay(mut self, delay: u64) -> Self {
+        self.config.frame_delay = delay;
+        self
+    }
+
+    /// ASCII map of the board (`Map::parse`), it replaces the dimensions
+
+    pub fn map(mut self, map: &str) -> Self {
+        self.config.set_map(map.to_string());
+        self
+    }
+
+    /// Goal regions, they replace the goal cells of the map
+
+    pub fn goals(mut self, goals: Vec<Goal>) -> Self {
+        self.config.goals = goals;
+        self
+    }
+
+    /// What happens to a move that would leave the board
+
+    pub fn boundary(mut self, boundary: Boundary) -> Self {
+        self.config.boundary = boundary;
+        self
+    }
+
+    /// Moves of the entities, the genomes must have one value per move
+
+    pub fn topology(mut self, topology: Topology) -> Self {
+        self.config.topology = topology;
+        self
+    }
+
+    /// Impassable cells of the board
+
+    pub fn walls(mut self, walls: Vec<Point>) -> Self {
+        self.config.walls = walls;
+        self
+    }
+
+    /// Run without drawing frames, sleeping or waiting for keys
+
+    pub fn headless(mut self, headless: bool) -> Self {
+        self.config.headless = headless;
+        self
+    }
+
+    /// Validate the config and create the Poblation
+    /// 
+    /// # Returns
+    /// 
+    /// * `Result<Poblation, ConfigError>` - The first invalid parameter found
+
+    pub fn build(self) -> Result<Poblation, ConfigError> {
+
+        self.config.validate()?;
+
+        let mut config = self.config;
+
+        let rng = match self.rng {
+            Some(rng) => rng,
+            None => random::generator(*config.seed.get_or_insert_with(random::entropy_seed)),
+        };
+
+        Ok(Poblation::with_rng(config, rng))
+    }
+}
+
+impl Poblation {
+
+    pub fn builder() -> SimulationBuilder {
+        SimulationBuilder::new()
+    }
+}
@@ -0,0 +1,196 @@
+use std::io;
+use std::path::Path;
+
+use plotters::prelude::*;
+use plotters::coord::Shift;
+
+use crate::stats::GenerationStats;
+
+/// ChartOptions struct
+///
+/// # Attributes
+///
+/// * `title` - Caption of the chart, none if empty
+/// * `moving_average` - Window of the moving average drawn over
+///   every series, in generations (None or 1 to skip it)
+/// * `size` - Image size in pixels (width, height)
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChartOptions {
+    pub title: String,
+    pub moving_average: Option<usize>,
+    pub size: (u32, u32),
+}
+
+impl Default for ChartOptions {
+
+    fn default() -> Self {
+        ChartOptions { title: String::new(), moving_average: None, size: (960, 540) }
+    }
+}
+
+/// Draw statistics series over the generations as an SVG document
+///
+/// # Arguments
+///
+/// * `generations` - Statistics of the run
+/// * `series` - Series names (`GenerationStats::value`)
+/// * `options` - Title, moving average and size
+
+pub fn svg(generations: &[GenerationStats], series: &[String], options: &ChartOptions) -> io::Result<String> {
+
+    let mut svg = String::new();
+
+    {
+        let root = SVGBackend::with_string(&mut svg, options.size).into_drawing_area();
+        draw(&root, generations, series, options)?;
+    }
+
+    Ok(svg)
+}
+
+/// Draw statistics series over the generations as RGB pixels
+
+pub fn pixels(generations: &[GenerationStats], series: &[String], options: &ChartOptions) -> io::Result<Vec<u8>> {
+
+    let (width, height) = options.size;
+    let mut pixels = vec![0; width as usize * height as usize * 3];
+
+    {
+        let root = BitMapBackend::with_buffer(&mut pixels, options.size).into_drawing_area();
+        draw(&root, generations, series, options)?;
+    }
+
+    Ok(pixels)
+}
+
+/// Write a chart of statistics series, SVG if the extension
+/// is `.svg` and PNG if it's `.png`
+
+pub fn write_chart(path: &Path, generations: &[GenerationStats], series: &[String], options: &ChartOptions) -> io::Result<()> {
+
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("svg") => std::fs::write(path, svg(generations, series, options)?),
+        Some("png") => {
+
+            let (width, height) = options.size;
+
+            let file = std::fs::File::create(path)?;
+            let mut encoder = png::Encoder::new(io::BufWriter::new(file), width, height);
+            encoder.set_color(png::ColorType::Rgb);
+            encoder.set_depth(png::BitDepth::Eight);
+
+            let mut writer = encoder.write_header().map_err(io::Error::other)?;
+            writer.write_image_data(&pixels(generations, series, options)?).map_err(io::Error::other)?;
+            writer.finish().map_err(io::Error::other)
+        },
+        _ => Err(io::Error::new(io::ErrorKind::InvalidInput, "the chart must be a .svg or .png file")),
+    }
+}
+
+/// Points (generation, value) of a series, generations
+/// without a value are skipped
+
+pub fn points(generations: &[GenerationStats], key: &str) -> Vec<(f64, f64)> {
+    generations.iter()
+        .filter_map(|stats| stats.value(key).map(|v| (stats.generation as f64, v)))
+        .collect()
+}
+
+/// Trailing moving average of a series, each point is the mean
+/// of itself and the `window - 1` points before it
+
+pub fn moving_average(points: &[(f64, f64)], window: usize) -> Vec<(f64, f64)> {
+
+    let window = window.max(1);
+
+    (0..points.len())
+        .map(|i| {
+            let start = (i + 1).saturating_sub(window);
+            let values = &points[start..=i];
+            (points[i].0, values.iter().map(|p| p.1).sum::<f64>() / values.len() as f64)
+        })
+        .collect()
+}
+
+fn draw<DB: DrawingBackend>(root: &DrawingArea<DB, Shift>, generations: &[GenerationStats],
+    series: &[String], options: &ChartOptions) -> io::Result<()> {
+
+    let error = |e: DrawingAreaErrorKind<DB::ErrorType>| io::Error::other(e.to_string());
+
+    let lines: Vec<(&String, Vec<(f64, f64)>)> = series.iter().map(|key| (key, points(generations, key))).collect();
+
+    // Rango de los ejes, con margen para que las líneas no toquen el borde
+
+    let last = generations.iter().map(|s| s.generation).max().unwrap_or(1).max(2) as f64;
+
+    let values = lines.iter().flat_map(|(_, points)| points.iter().map(|p| p.1));
+    let (min, max) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| (min.min(v), max.max(v)));
+
+    let (min, max) = match min.is_finite() {
+        true if max > min => (min - (max - min) * 0.05, max + (max - min) * 0.05),
+        true => (min - 1.0, max + 1.0),
+        false => (0.0, 1.0),
+    };
+
+    root.fill(&WHITE).map_err(error)?;
+
+    let mut builder = ChartBuilder::on(root);
+
+    builder.margin(16).x_label_area_size(48).y_label_area_size(64);
+
+    if !options.title.is_empty() {
+        builder.caption(&options.title, ("sans-serif", 24));
+    }
+
+    let mut chart = builder.build_cartesian_2d(1.0..last, min..max).map_err(error)?;
+
+    let y_desc = match series {
+        [key] => key.as_str(),
+        _ => "value",
+    };
+
+    chart.configure_mesh()
+        .x_desc("generation")
+        .y_desc(y_desc)
+        .x_label_formatter(&|x| format!("{:.0}", x))
+        .label_style(("sans-serif", 14))
+        .axis_desc_style(("sans-serif", 16))
+        .draw()
+        .map_err(error)?;
+
+    let window = options.moving_average.filter(|&w| w > 1);
+
+    for (i, (key, points)) in lines.iter().enumerate() {
+
+        let color = Palette99::pick(i).to_rgba();
+
+        // Con media móvil la serie original queda de fondo
+
+        let style = match window {
+            Some(_) => color.mix(0.35).stroke_width(1),
+            None => color.stroke_width(2),
+        };
+
+        chart.draw_series(LineSeries::new(points.iter().copied(), style))
+            .map_err(error)?
+            .label(key.as_str())
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], style));
+
+        if let Some(window) = window {
+            chart.draw_series(LineSeries::new(moving_average(points, window), color.stroke_width(2)))
+                .map_err(error)?
+                .label(format!("{} ({} gen. average)", key, window))
+                .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color.stroke_width(2)));
+        }
+    }
+
+    chart.configure_series_labels()
+        .background_style(WHITE.mix(0.85))
+        .border_style(BLACK)
+        .label_font(("sans-serif", 14))
+        .draw()
+        .map_err(error)?;
+
+    root.present().map_err(error)
+}
@@ -0,0 +1,135 @@
+use std::fs::File;
+use std::path::Path;
+use std::collections::HashSet;
+use std::io::{self, BufReader};
+
+use serde::{Deserialize, Serialize};
+
+use crate::utils;
+use crate::board::Board;
+use crate::entity::Entity;
+use crate::genome::HallOfFame;
+use crate::random::SimRng;
+use crate::poblation::Poblation;
+use crate::stats::GenerationStats;
+use crate::config::SimulationConfig;
+
+/// Checkpoint struct
+///
+/// Full state of a simulation between two generations, restoring it
+/// continues the run exactly as if it had never been interrupted
+///
+/// # Attributes
+///
+/// * `config` - Simulation configuration (with the recorded seed)
+/// * `actual_gen` - Next generation to run
+/// * `entities` - Entities of the next generation, already placed
+/// * `generations` - Statistics of the finished generations
+/// * `rng` - State of the random generator
+/// * `hall_of_fame` - Best genomes of the finished generations, if the run keeps them
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub config: SimulationConfig,
+    pub actual_gen: usize,
+    pub entities: Vec<Entity>,
+    pub generations: Vec<GenerationStats>,
+    pub rng: SimRng,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hall_of_fame: Option<HallOfFame>,
+}
+
+impl Checkpoint {
+
+    /// Capture the state of a Poblation, it must be between two
+    /// generations (no iteration of `actual_gen` has run yet)
+
+    pub fn capture(poblation: &Poblation) -> Self {
+
+        assert_eq!(poblation.iteration, 0, "checkpoints must be taken between generations");
+
+        Checkpoint {
+            config: poblation.config.clone(),
+            actual_gen: poblation.actual_gen,
+            entities: poblation.entities.clone(),
+            generations: poblation.generations.clone(),
+            rng: poblation.rng.clone(),
+            hall_of_fame: None,
+        }
+    }
+
+    /// Create the Poblation of the checkpoint, the hall of
+    /// fame has to be taken before
+
+    pub fn restore(self) -> Poblation {
+
+        let mut poblation = Poblation::from_entities(self.config, self.entities, self.rng);
+
+        poblation.generations = self.generations;
+        poblation.actual_gen = self.actual_gen;
+
+        poblation
+    }
+
+    /// Write the checkpoint as JSON, the file is replaced
+    /// atomically so an interrupted write keeps the previous one
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        utils::replace(path, |writer| Ok(serde_json::to_writer(writer, self)?))
+    }
+
+    /// Read a checkpoint file, its configuration must be valid and its
+    /// entities must be the ones of a generation that hasn't started:
+    /// `config.sample` entities on different free start cells, with one
+    /// direction value per move of the topology
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+
+        let reader = BufReader::new(File::open(path)?);
+        let checkpoint: Checkpoint = serde_json::from_reader(reader)?;
+
+        checkpoint.config.validate().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        checkpoint.check_entities().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        Ok(checkpoint)
+    }
+
+    /// Check the entities against the configuration
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), String>` - The first invalid entity
+
+    fn check_entities(&self) -> Result<(), String> {
+
+        let config = &self.config;
+        let board = Board::new(config);
+
+        if self.entities.len() != config.sample {
+            return Err(format!("the checkpoint has {} entities, the sample is {}", self.entities.len(), config.sample))
+        }
+
+        let mut occupied = HashSet::new();
+
+        for (index, entity) in self.entities.iter().enumerate() {
+
+            if entity.values.len() != config.topology.genes() {
+                return Err(format!("entity {} has {} direction values, {} needs {}", index, entity.values.len(), config.topology, config.topology.genes()))
+            }
+
+            let Some(position) = entity.position else {
+                return Err(format!("entity {} has no position", index))
+            };
+
+            if !board.is_start(position) {
+                return Err(format!("entity {} is at ({}, {}), which isn't a free start cell", index, position.x, position.y))
+            }
+
+            if !occupied.insert(position) {
+                return Err(format!("entities share the cell ({}, {})", position.x, position.y))
+            }
+        }
+
+        Ok(())
+    }
+}
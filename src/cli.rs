@@ -0,0 +1,386 @@
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+use genetic::random;
+use genetic::map::Map;
+use genetic::board::Boundary;
+use genetic::genome::HallOfFame;
+use genetic::topology::Topology;
+use genetic::replay::ReplayGenerations;
+use genetic::{ConfigError, GenerationStats, SimulationConfig};
+
+/// Cli struct
+///
+/// Command line interface of the simulator
+
+#[derive(Debug, Parser)]
+#[command(name = "genetic", version, about = "Genetic algorithm that evolves entities to cross the board", long_about = None)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+
+    /// Run the simulation showing the board and the statistics charts
+    Run(SimulationArgs),
+
+    /// Run a single generation printing every movement and murder
+    Debug(SimulationArgs),
+
+    /// Play a replay file written by --replay-out
+    ///
+    /// Keys: space pause, right/left step, +/- speed, n/p next/previous generation,
+    /// g jump to a generation, q quit
+    Replay {
+
+        /// Replay file (.rpl)
+        file: PathBuf,
+
+        /// Generation to start from, the first recorded one by default
+        #[arg(long)]
+        generation: Option<usize>,
+
+        /// Delay between frames in milliseconds, the one of the recorded run by default
+        #[arg(long)]
+        frame_delay: Option<u64>,
+    },
+
+    /// Run the simulation silently and plot the statistics
+    Plot {
+        #[command(flatten)]
+        simulation: SimulationArgs,
+
+        /// Statistics series to plot: murders, winners, best_fitness, mean_fitness, worst_fitness,
+        /// killer_ratio, survivors, mean_steps_to_goal, blocked_moves, falls, diversity,
+        /// gene_mean_<i> or gene_variance_<i>
+        #[arg(long, value_parser = parse_series, value_delimiter = ',', default_value = "murders,winners")]
+        series: Vec<String>,
+
+        /// Image (.svg or .png) where every series is drawn in one chart instead of the terminal
+        #[arg(long, value_parser = parse_chart_path)]
+        chart_out: Option<PathBuf>,
+
+        /// Draw the moving average of every series over N generations
+        #[arg(long)]
+        moving_average: Option<usize>,
+
+        /// Title of the chart
+        #[arg(long, default_value = "")]
+        title: String,
+    },
+
+    /// Export the frames of a replay file as SVG or PNG images or as animated GIFs
+    Render {
+
+        /// Replay file (.rpl)
+        file: PathBuf,
+
+        /// Directory where the images are written
+        #[arg(long, default_value = ".")]
+        out_dir: PathBuf,
+
+        /// Image format, gif writes one animation per generation
+        #[arg(long, value_enum, default_value_t = ImageFormat::Gif)]
+        format: ImageFormat,
+
+        /// Generation to export, every recorded one by default
+        #[arg(long)]
+        generation: Option<usize>,
+
+        /// Single iteration to export (0 is the initial state), every one by default
+        #[arg(long)]
+        iteration: Option<usize>,
+
+        /// Delay between GIF frames in milliseconds, the one of the recorded run by default
+        #[arg(long)]
+        frame_delay: Option<u64>,
+    },
+
+    /// List the bundled maps
+    Maps,
+
+    /// Continue a simulation from a checkpoint file
+    Resume {
+
+        /// Checkpoint written by --checkpoint
+        file: PathBuf,
+
+        #[command(flatten)]
+        outputs: OutputArgs,
+
+        /// Delay between frames in milliseconds
+        #[arg(long)]
+        frame_delay: Option<u64>,
+
+        /// Run without drawing the board, sleeping or waiting for keys
+        #[arg(long)]
+        headless: bool,
+    },
+}
+
+/// Image format of the `render` subcommand
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ImageFormat {
+    Svg,
+    Png,
+    Gif,
+}
+
+fn parse_chart_path(path: &str) -> Result<PathBuf, String> {
+    match path.ends_with(".svg") || path.ends_with(".png") {
+        true => Ok(PathBuf::from(path)),
+        false => Err("the chart must be a .svg or .png file".to_string()),
+    }
+}
+
+fn parse_series(key: &str) -> Result<String, String> {
+    match GenerationStats::is_series(key) {
+        true => Ok(key.to_string()),
+        false => Err(format!("unknown series '{}'", key)),
+    }
+}
+
+/// SimulationArgs struct
+///
+/// Flags shared by every subcommand, each one overrides
+/// the matching SimulationConfig parameter of the config file
+
+#[derive(Debug, Args)]
+pub struct SimulationArgs {
+
+    /// TOML or JSON file with the simulation parameters
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+
+    /// Bundled map (open, wall, funnel, maze) or ASCII map file, it replaces --rows and --cols
+    #[arg(long)]
+    pub map: Option<String>,
+
+    /// What happens to a move that leaves the board: blocked, wrap (top and bottom rows joined), reflect or lethal
+    #[arg(long)]
+    pub boundary: Option<Boundary>,
+
+    /// Moves of the entities: von-neumann (4), moore (8), moore-stay (9) or hex (6)
+    #[arg(long)]
+    pub topology: Option<Topology>,
+
+    /// Genome file (written by --hall-of-fame) used for the first generation instead of random entities,
+    /// the genomes are recorded in the resolved config
+    #[arg(long)]
+    pub genomes: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub outputs: OutputArgs,
+
+    /// Seed of the random generator (decimal or 0x hex), a random one is drawn and printed if omitted
+    #[arg(long, value_parser = random::parse_seed)]
+    pub seed: Option<u64>,
+
+    /// Number of rows of the board
+    #[arg(long)]
+    pub rows: Option<usize>,
+
+    /// Number of columns of the board
+    #[arg(long)]
+    pub cols: Option<usize>,
+
+    /// Number of entities per generation
+    #[arg(long)]
+    pub sample: Option<usize>,
+
+    /// Maximum number of generations
+    #[arg(long)]
+    pub generations: Option<usize>,
+
+    /// Movements per entity on each generation
+    #[arg(long)]
+    pub iterations: Option<usize>,
+
+    /// Probability of mutating a child
+    #[arg(long)]
+    pub mutation_probability: Option<f64>,
+
+    /// Probability of a new entity being a killer
+    #[arg(long)]
+    pub killer_probability: Option<f64>,
+
+    /// Selection pressure of the parent distribution
+    #[arg(long)]
+    pub p: Option<f64>,
+
+    /// Show the board every N generations
+    #[arg(long)]
+    pub show_threshold: Option<usize>,
+
+    /// Delay between frames in milliseconds
+    #[arg(long)]
+    pub frame_delay: Option<u64>,
+
+    /// Run without drawing the board, sleeping or waiting for keys
+    #[arg(long, overrides_with = "no_headless")]
+    pub headless: bool,
+
+    /// Draw the board even if the config file sets headless
+    #[arg(long, overrides_with = "headless")]
+    pub no_headless: bool,
+}
+
+/// OutputArgs struct
+///
+/// Files written by a run, shared by the subcommands
+/// that start a simulation and by `resume`
+
+#[derive(Debug, Args)]
+pub struct OutputArgs {
+
+    /// Directory where the resolved config is written
+    #[arg(long)]
+    pub out_dir: Option<PathBuf>,
+
+    /// JSON Lines file where every simulation event is written
+    #[arg(long)]
+    pub events_out: Option<PathBuf>,
+
+    /// File (.csv or .json) where the per-generation statistics and run metadata are written
+    #[arg(long)]
+    pub stats_out: Option<PathBuf>,
+
+    /// File where the simulation state is saved to be continued with `resume`
+    #[arg(long)]
+    pub checkpoint: Option<PathBuf>,
+
+    /// Save the checkpoint every N generations
+    #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u64).range(1..))]
+    pub checkpoint_every: u64,
+
+    /// File where the best genomes that reached the goal are written
+    #[arg(long)]
+    pub hall_of_fame: Option<PathBuf>,
+
+    /// Number of genomes kept in the hall of fame
+    #[arg(long, default_value_t = 10)]
+    pub hall_of_fame_size: usize,
+
+    /// Self-contained HTML report written at the end of the run
+    #[arg(long)]
+    pub report: Option<PathBuf>,
+
+    /// Replay file (.rpl) where the chosen generations are recorded
+    #[arg(long)]
+    pub replay_out: Option<PathBuf>,
+
+    /// Generations written to the replay file: "all" or a comma separated list,
+    /// the converged (or last) generation is always written
+    #[arg(long)]
+    pub replay_generations: Option<ReplayGenerations>,
+}
+
+impl SimulationArgs {
+
+    /// Resolve the simulation config: defaults, then the
+    /// config file (if any) and finally the command line flags
+
+    pub fn resolve(&self) -> Result<SimulationConfig, ConfigError> {
+
+        let mut config = match &self.config {
+            Some(path) => SimulationConfig::from_file(path)?,
+            None => SimulationConfig::default(),
+        };
+
+        if let Some(map) = &self.map {
+            config.set_map(Map::load(map)?);
+        }
+
+        // Los genomas se guardan en la configuración para poder repetir la ejecución
+
+        if let Some(path) = &self.genomes {
+
+            let file = |message: String| ConfigError::File { path: path.clone(), message };
+
+            let genomes = HallOfFame::load(path).map_err(|e| file(e.to_string()))?.genomes;
+
+            if genomes.is_empty() {
+                return Err(file("it has no genomes".to_string()))
+            }
+
+            config.genomes = genomes;
+        }
+
+        self.apply(&mut config);
+
+        Ok(config)
+    }
+
+    /// Override the config parameters with the given flags
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Configuration to update
+
+    pub fn apply(&self, config: &mut SimulationConfig) {
+
+        if self.seed.is_some() {
+            config.seed = self.seed;
+        }
+
+        if let Some(rows) = self.rows {
+            config.dimensions.0 = rows;
+        }
+
+        if let Some(cols) = self.cols {
+            config.dimensions.1 = cols;
+        }
+
+        if let Some(boundary) = self.boundary {
+            config.boundary = boundary;
+        }
+
+        if let Some(topology) = self.topology {
+            config.topology = topology;
+        }
+
+        if let Some(sample) = self.sample {
+            config.sample = sample;
+        }
+
+        if let Some(generations) = self.generations {
+            config.n_generations = generations;
+        }
+
+        if let Some(iterations) = self.iterations {
+            config.n_iterations = iterations;
+        }
+
+        if let Some(probability) = self.mutation_probability {
+            config.mutation_probability = probability;
+        }
+
+        if let Some(probability) = self.killer_probability {
+            config.killer_probability = probability;
+        }
+
+        if let Some(p) = self.p {
+            config.p = p;
+        }
+
+        if let Some(threshold) = self.show_threshold {
+            config.show_threshold = threshold;
+        }
+
+        if let Some(delay) = self.frame_delay {
+            config.frame_delay = delay;
+        }
+
+        if self.headless {
+            config.headless = true;
+        }
+
+        if self.no_headless {
+            config.headless = false;
+        }
+    }
+}
@@ -0,0 +1,325 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::map::Map;
+use crate::genome::Genome;
+use crate::topology::Topology;
+use crate::board::{Board, Boundary, Goal};
+use crate::position::Point;
+
+/// SimulationConfig struct
+///
+/// Runtime parameters of the simulation, every knob
+/// that used to be a compile-time constant
+///
+/// # Attributes
+///
+/// * `sample` - Number of entities per generation
+/// * `n_iterations` - Movements per entity on each generation
+/// * `dimensions` - Board dimensions (rows, columns), they must be
+///   the ones of the map if there's one
+/// * `n_generations` - Maximum number of generations
+/// * `show_threshold` - Show the board every `show_threshold` generations
+/// * `seed` - Seed of the random generator, a new one is drawn
+///   from entropy (and recorded here) if None
+/// * `mutation_probability` - Probability of mutating a child
+/// * `killer_probability` - Probability of a new entity being a killer
+/// * `p` - Selection pressure of the geometric parent distribution
+/// * `frame_delay` - Milliseconds to wait after drawing a frame
+/// * `headless` - Skip every frame, sleep and key prompt
+/// * `boundary` - What happens to a move that would leave the board
+/// * `topology` - Moves of the entities, one direction value per move
+/// * `walls` - Impassable cells of the board
+/// * `map` - ASCII map with the walls, start and goal cells (`Map`),
+///   the two first columns are the start and the last one the goal if None
+/// * `goals` - Goal regions, they replace the goal cells of the map
+/// * `genomes` - Genomes of the first generation (`--genomes`), they're
+///   repeated in order if there are fewer than `sample`, random entities if empty
+/// 
+/// Missing parameters in a config file take their default value
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SimulationConfig {
+    pub sample: usize,
+    pub n_iterations: usize,
+    pub dimensions: (usize, usize),
+    pub n_generations: usize,
+    pub show_threshold: usize,
+    #[serde(skip_serializing_if = "Option::is_none", with = "crate::random::seed_serde")]
+    pub seed: Option<u64>,
+    pub mutation_probability: f64,
+    pub killer_probability: f64,
+    pub p: f64,
+    pub frame_delay: u64,
+    pub headless: bool,
+    pub boundary: Boundary,
+    pub topology: Topology,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub walls: Vec<Point>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub map: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub goals: Vec<Goal>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub genomes: Vec<Genome>,
+}
+
+impl Default for SimulationConfig {
+
+    fn default() -> Self {
+        SimulationConfig {
+            sample: 16,
+            n_iterations: 100,
+            dimensions: (12, 20),
+            n_generations: 500,
+            show_threshold: 10,
+            seed: None,
+            mutation_probability: 0.05,
+            killer_probability: 0.2,
+            p: 0.5,
+            frame_delay: 25,
+            headless: false,
+            boundary: Boundary::Blocked,
+            topology: Topology::Moore,
+            walls: Vec::new(),
+            map: None,
+            goals: Vec::new(),
+            genomes: Vec::new(),
+        }
+    }
+}
+
+impl SimulationConfig {
+
+    /// Load a configuration from a TOML or JSON file,
+    /// the format is chosen by the file extension
+    /// 
+    /// # Arguments
+    /// 
+    /// * `path` - Path of the config file
+
+    pub fn from_file(path: &Path) -> Result<Self, ConfigError> {
+
+        let file_error = |message: String| ConfigError::File { path: path.to_path_buf(), message };
+
+        let content = fs::read_to_string(path).map_err(|e| file_error(e.to_string()))?;
+
+        let mut config: SimulationConfig = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => serde_json::from_str(&content).map_err(|e| file_error(e.to_string()))?,
+            _ => toml::from_str(&content).map_err(|e| file_error(e.to_string()))?,
+        };
+
+        // Las dimensiones del tablero son las del mapa
+
+        if let Some(map) = config.map.take() {
+            config.set_map(map);
+        }
+
+        Ok(config)
+    }
+
+    /// Use an ASCII map as the board, the dimensions are replaced by the
+    /// ones of the map (an invalid map is reported by `validate`)
+    ///
+    /// # Arguments
+    ///
+    /// * `map` - Text of the map (`Map::parse`)
+
+    pub fn set_map(&mut self, map: String) {
+
+        if let Ok(parsed) = Map::parse(&map) {
+            self.dimensions = parsed.dimensions;
+        }
+
+        self.map = Some(map);
+    }
+
+    /// Serialize the configuration as TOML, it can be
+    /// loaded back with `SimulationConfig::from_file`
+
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).expect("SimulationConfig is always serializable")
+    }
+
+    /// Write the configuration as `config.toml` inside the given directory
+    /// 
+    /// # Arguments
+    /// 
+    /// * `dir` - Results directory, it's created if it doesn't exist
+
+    pub fn write_to(&self, dir: &Path) -> Result<PathBuf, ConfigError> {
+
+        let path = dir.join("config.toml");
+        let file_error = |e: std::io::Error| ConfigError::File { path: path.clone(), message: e.to_string() };
+
+        fs::create_dir_all(dir).map_err(file_error)?;
+        fs::write(&path, self.to_toml()).map_err(file_error)?;
+
+        Ok(path)
+    }
+
+    /// Check that the configuration describes a runnable simulation
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), ConfigError>` - The first invalid parameter found
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+
+        let (rows, cols) = self.dimensions;
+
+        if let Some(map) = &self.map {
+
+            let map = Map::parse(map)?;
+
+            if map.dimensions != self.dimensions {
+                return Err(ConfigError::Map(format!("the map is {}x{} but the dimensions are {}x{}", map.dimensions.0, map.dimensions.1, rows, cols)))
+            }
+        }
+
+        // Se necesitan al menos dos filas y una columna entre la zona
+        // de inicio (columnas 0 y 1) y la meta del tablero sin mapa
+
+        if self.map.is_none() && (rows < 2 || cols < 3) {
+            return Err(ConfigError::Dimensions(self.dimensions))
+        }
+
+        // Con un número impar de filas el borde une dos filas pares, que no son vecinas
+
+        if self.topology == Topology::Hex && self.boundary == Boundary::Wrap && !rows.is_multiple_of(2) {
+            return Err(ConfigError::HexWrap(rows))
+        }
+
+        let board = Board::new(self);
+
+        if let Some(&wall) = self.walls.iter().find(|&&w| !board.contains(w)) {
+            return Err(ConfigError::Outside("wall", wall))
+        }
+
+        for (index, goal) in self.goals.iter().enumerate() {
+
+            if let Some(point) = goal.points().into_iter().find(|&p| !board.contains(p)) {
+                return Err(ConfigError::Outside("goal cell", point))
+            }
+
+            // Las entidades nacerían en la meta y la generación convergería sin moverse
+
+            if let Some(point) = goal.points().into_iter().find(|&p| board.is_start(p)) {
+                return Err(ConfigError::GoalOnStart(point))
+            }
+
+            if !goal.weight.is_finite() || goal.weight <= 0.0 {
+                return Err(ConfigError::GoalWeight(index, goal.weight))
+            }
+        }
+
+        if board.goal_capacity() == 0 {
+            return Err(ConfigError::Goal)
+        }
+
+        // Los hijos se crean de a pares y deben caber en las celdas de inicio libres
+
+        let capacity = board.start_capacity();
+
+        if self.sample == 0 || !self.sample.is_multiple_of(2) || self.sample > capacity {
+            return Err(ConfigError::Sample { sample: self.sample, capacity })
+        }
+
+        // Cada genoma tiene un valor por movimiento de la topología
+
+        if let Some(index) = self.genomes.iter().position(|g| !g.is_valid(self.topology)) {
+            return Err(ConfigError::Genome(index))
+        }
+
+        if self.n_iterations == 0 {
+            return Err(ConfigError::Zero("n_iterations"))
+        }
+
+        if self.n_generations == 0 {
+            return Err(ConfigError::Zero("n_generations"))
+        }
+
+        if self.show_threshold == 0 {
+            return Err(ConfigError::Zero("show_threshold"))
+        }
+
+        if !(0.0..=1.0).contains(&self.mutation_probability) {
+            return Err(ConfigError::Probability("mutation_probability", self.mutation_probability))
+        }
+
+        if !(0.0..=1.0).contains(&self.killer_probability) {
+            return Err(ConfigError::Probability("killer_probability", self.killer_probability))
+        }
+
+        // Con p = 0 las probabilidades no se pueden normalizar y con p = 1
+        // solo se podría elegir al primer padre
+
+        if self.p <= 0.0 || self.p >= 1.0 || self.p.is_nan() {
+            return Err(ConfigError::Probability("p", self.p))
+        }
+
+        Ok(())
+    }
+}
+
+/// ConfigError enum
+///
+/// Reasons why a SimulationConfig can't be run
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConfigError {
+    Dimensions((usize, usize)),
+    Sample { sample: usize, capacity: usize },
+    Zero(&'static str),
+    Probability(&'static str, f64),
+    File { path: PathBuf, message: String },
+    Genome(usize),
+    Outside(&'static str, Point),
+    Goal,
+    GoalWeight(usize, f64),
+    Map(String),
+    HexWrap(usize),
+    GoalOnStart(Point),
+}
+
+impl fmt::Display for ConfigError {
+
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Dimensions((rows, cols)) => {
+                write!(f, "dimensions must be at least 2 rows by 3 columns, got {}x{}", rows, cols)
+            },
+            ConfigError::Sample { sample, capacity } => {
+                write!(f, "sample must be an even number between 2 and {} (free start cells), got {}", capacity, sample)
+            },
+            ConfigError::Zero(name) => write!(f, "{} must be greater than 0", name),
+            ConfigError::Probability(name, value) => {
+                write!(f, "{} must be a valid probability, got {}", name, value)
+            },
+            ConfigError::File { path, message } => write!(f, "{}: {}", path.display(), message),
+            ConfigError::Genome(index) => {
+                write!(f, "genome {} must have one non-negative value per move of the topology, not all zero", index)
+            },
+            ConfigError::Outside(name, point) => {
+                write!(f, "{} ({}, {}) is outside the board", name, point.x, point.y)
+            },
+            ConfigError::Goal => write!(f, "the board must have at least one free goal cell"),
+            ConfigError::GoalWeight(index, weight) => {
+                write!(f, "goal {} must have a positive weight, got {}", index, weight)
+            },
+            ConfigError::Map(message) => write!(f, "invalid map: {}", message),
+            ConfigError::GoalOnStart(point) => {
+                write!(f, "goal cell ({}, {}) is a start cell", point.x, point.y)
+            },
+            ConfigError::HexWrap(rows) => {
+                write!(f, "a hex board with the wrap boundary needs an even number of rows, got {}", rows)
+            },
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
@@ -1,16 +1,44 @@
 
 use rand::Rng;
 use uuid::Uuid;
-use std::ops::Add;
 use colored::CustomColor;
+use serde::{Deserialize, Serialize};
 
 use crate::utils;
+use crate::board::Board;
 use crate::position::*;
-use crate::constants::*;
-use crate::random::random;
+use crate::config::SimulationConfig;
 
 pub type Color<T> = (T, T, T);
 
+/// Movement enum
+/// 
+/// Result of `Entity::next_position`
+/// 
+/// * `To` - The entity can move to the point (it's the current
+///   position if the entity is already on the goal)
+/// * `Blocked` - The move would leave the board or hit a wall, the entity stays
+/// * `Fall` - The move crossed a lethal edge, the entity dies
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Movement {
+    To(Point),
+    Blocked,
+    Fall,
+}
+
+/// Mutation enum
+/// 
+/// Gene changed by `Entity::mutate`, a direction value (by index)
+/// or the killer flag
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Mutation {
+    Value(usize),
+    Killer,
+}
+
 /// Entity struct
 /// 
 /// # Attributes
@@ -27,20 +55,39 @@ pub type Color<T> = (T, T, T);
 /// * `is_killer` - Check if the entity is a killer
 /// * `get_position` - Get the entity position
 /// * `next_position` - Get the next entity position
+/// * `crossover` - Cross two entities into a pair of children
 /// 
 /// # Examples
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Entity {
     pub id: Uuid,
     pub values: Vec<f64>,
     pub killer: bool,
     pub alive: bool,
     pub position: Position,
+    #[serde(with = "color_serde")]
     pub color: CustomColor,
     pub fitness: usize,
 }
 
+/// Serde representation of the entity color as [r, g, b]
+
+mod color_serde {
+
+    use colored::CustomColor;
+    use serde::{Deserialize, Deserializer, Serializer, Serialize};
+
+    pub fn serialize<S: Serializer>(color: &CustomColor, serializer: S) -> Result<S::Ok, S::Error> {
+        [color.r, color.g, color.b].serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<CustomColor, D::Error> {
+        let [r, g, b] = <[u8; 3]>::deserialize(deserializer)?;
+        Ok(CustomColor::new(r, g, b))
+    }
+}
+
 impl Entity {
   
     /// Create a new Entity
@@ -48,26 +95,28 @@ impl Entity {
     /// # Arguments
     /// 
     /// * `position` - Entity position (Option<Point>)
+    /// * `config` - Simulation configuration
+    /// * `rng` - Random generator of the simulation
     /// 
     /// # Returns
     /// 
-    /// * `Entity` - New Entity with normalized 
-    /// direction values and killer flag set randomly with a 10% chance
+    /// * `Entity` - New Entity with normalized direction values and
+    ///   killer flag set randomly with `config.killer_probability`
 
-    pub fn new(position: Position) -> Self {
+    pub fn new<R: Rng>(position: Position, config: &SimulationConfig, rng: &mut R) -> Self {
 
-        let mut values = vec![0.0; 8];
+        let mut values = vec![0.0; config.topology.genes()];
         
-        for i in 0..8 {
-            values[i] = utils::random().gen::<f64>()
+        for value in values.iter_mut() {
+            *value = rng.gen::<f64>()
         }
 
         values = utils::normalize(&values);
 
-        let color = utils::to_rgb((values[2], values[3], values[4]));
-        let killer = utils::random().gen_bool(KILLER_PROBABILITY);
+        let color = utils::values_color(&values);
+        let killer = rng.gen_bool(config.killer_probability);
         
-        Entity { id: utils::uuid(), values, killer, position, alive: true, color, fitness: N_ITERATIONS }
+        Entity { id: utils::uuid(rng), values, killer, position, alive: true, color, fitness: config.n_iterations }
     }
 
     /// Create a new Entity from a given values
@@ -78,18 +127,22 @@ impl Entity {
     /// * `killer` - Entity killer flag
     /// * `position` - Entity position (Point)
     /// * `color` - Entity color
+    /// * `rng` - Random generator used for the identifier
+    /// 
+    /// The fitness starts at 0, it's set when the
+    /// entity is placed on the board (`Poblation::assign_positions`)
 
-    pub fn from(values: Vec<f64>, killer: bool, 
-        position: Position, color: CustomColor) -> Self {
+    pub fn from<R: Rng>(values: Vec<f64>, killer: bool, 
+        position: Position, color: CustomColor, rng: &mut R) -> Self {
 
         Entity { 
-            id: utils::uuid(), 
+            id: utils::uuid(rng), 
             values, 
             killer, 
             position, 
             color, 
             alive: true,
-            fitness: N_ITERATIONS,
+            fitness: 0,
         }
     }
 
@@ -98,20 +151,26 @@ impl Entity {
     }
 
     pub fn get_position(&self) -> Point {
-        self.position.clone().unwrap()
+        self.position.unwrap()
     }
 
     /// Get the next entity position
     /// 
+    /// # Arguments
+    /// 
+    /// * `board` - Board of the simulation
+    /// * `rng` - Random generator of the simulation
+    /// 
     /// # Returns
     /// 
-    /// * `Point` - Next entity position (Point)
+    /// * `Movement` - Next entity position, Blocked if it's a wall or the edge
+    ///   of the board blocks it and Fall if the edge is lethal (`Board::destination`)
 
-    pub fn next_position(&mut self) -> Point {
+    pub fn next_position<R: Rng>(&mut self, board: &Board, rng: &mut R) -> Movement {
     
         // Generate a random number between 0 and 1
         
-        let prob = utils::random().gen::<f64>();
+        let prob = rng.gen::<f64>();
         let cumulatives = utils::cumulative(&self.values);
 
         // Find the index of the first value in the cumulative vector
@@ -119,72 +178,87 @@ impl Entity {
 
         let index = cumulatives.iter().position(|&v| v > prob).unwrap();
 
-        // That index is the direction to move
-
-        let dir = DIRECTIONS[index];
-
         // Get the current position of the entity
     
         let current_pos = self.get_position();
 
-        if current_pos.x == (DIMENSIONS.1 - 1) as isize {
-            return current_pos
+        if board.is_goal(current_pos) {
+            return Movement::To(current_pos)
         }
 
-        // Calculate the next position and verify the limits
-        // negatives and greater than the row limit
-    
-        let next_pos = current_pos + dir;
+        // That index is the move, the board applies the topology,
+        // the walls and the rule of its edges
 
-        // Check if next_pos is outside the limits
-        if next_pos.x < 0 || next_pos.y < 0 || next_pos.x >= DIMENSIONS.1 as isize || next_pos.y >= DIMENSIONS.0 as isize {
-            return current_pos
+        let movement = board.destination(current_pos, index);
+
+        if let Movement::To(_) = movement {
+            self.fitness -= 1;
         }
 
-        self.fitness -= 1;
-        next_pos
+        movement
     }
 
-    pub fn mutate(&mut self) {
+    /// Mutate the entity with `config.mutation_probability`, changing
+    /// a random direction value or flipping the killer flag
+    /// 
+    /// # Returns
+    /// 
+    /// * `Option<Mutation>` - The mutated gene, None if there was no mutation
+
+    pub fn mutate<R: Rng>(&mut self, config: &SimulationConfig, rng: &mut R) -> Option<Mutation> {
+
+        if rng.gen::<f64>() <= config.mutation_probability {
 
-        if random().gen::<f64>() <= MUTATION_PROBABILTY {
+            // El último índice corresponde al gen asesino
 
-            let index = utils::random().gen_range(0..=8);
+            let index = rng.gen_range(0..=self.values.len());
     
-            if index == 8 {
+            if index == self.values.len() {
                 self.killer = !self.killer;
-                return
+                return Some(Mutation::Killer)
             }
     
-            self.values[index] = utils::random().gen::<f64>();
+            self.values[index] = rng.gen::<f64>();
             self.values = utils::normalize(&self.values);
+
+            return Some(Mutation::Value(index))
         }
+
+        None
     }
-}
-    
-impl Add for Entity {
 
-    type Output = (Entity, Entity);
+    /// Cross two entities into a pair of children
+    /// 
+    /// The first child takes the first half of `self` values and the second
+    /// half of `rhs` values (and the `rhs` killer flag), the second child
+    /// takes the remaining halves. With an odd number of values the
+    /// second half is the longest
+    /// 
+    /// # Arguments
+    /// 
+    /// * `rhs` - The other parent
+    /// * `rng` - Random generator used for the children identifiers
+
+    pub fn crossover<R: Rng>(&self, rhs: &Entity, rng: &mut R) -> (Entity, Entity) {
 
-    fn add(self, rhs: Self) -> Self::Output {
+        let half = self.values.len() / 2;
 
-        let c1_1 = self.values[0..=3].to_vec();
-        let c1_2 = self.values[4..=7].to_vec();
+        let c1_1 = self.values[..half].to_vec();
+        let c1_2 = self.values[half..].to_vec();
         
-        let c2_1 = rhs.values[0..=3].to_vec();
-        let c2_2 = rhs.values[4..=7].to_vec();
+        let c2_1 = rhs.values[..half].to_vec();
+        let c2_2 = rhs.values[half..].to_vec();
 
         let children_1_v = utils::normalize(&[c1_1, c2_2].concat());
         let children_2_v = utils::normalize(&[c2_1, c1_2].concat());
 
-        let children_1_color = utils::to_rgb((children_1_v[2], children_1_v[3], children_1_v[4]));
-        let children_2_color = utils::to_rgb((children_2_v[2], children_2_v[3], children_2_v[4]));
+        let children_1_color = utils::values_color(&children_1_v);
+        let children_2_color = utils::values_color(&children_2_v);
 
-        let mut children_1 = Entity::from(children_1_v, rhs.killer, Position::None, children_1_color);
-        let mut children_2 = Entity::from(children_2_v, self.killer, Position::None, children_2_color);
+        let children_1 = Entity::from(children_1_v, rhs.killer, Position::None, children_1_color, rng);
+        let children_2 = Entity::from(children_2_v, self.killer, Position::None, children_2_color, rng);
 
-        children_1.mutate();
-        children_2.mutate();
+        // La mutación depende de la configuración, se aplica en Poblation::selection
 
         (children_1, children_2)
     }
@@ -0,0 +1,156 @@
+use std::io::{self, Write};
+
+use uuid::Uuid;
+use serde::Serialize;
+
+use crate::position::Point;
+use crate::stats::GenerationStats;
+use crate::entity::{Entity, Mutation};
+use crate::observer::SimulationObserver;
+
+/// Event enum
+///
+/// One record of the JSON Lines event log, the `event`
+/// field holds the name of the variant
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    GenerationStart { generation: usize, entities: Vec<EntityState> },
+    Move { generation: usize, iteration: usize, entity: Uuid, from: Point, to: Point },
+    Kill { generation: usize, iteration: usize, killer: EntityState, victim: EntityState },
+    MutualKill { generation: usize, iteration: usize, first: EntityState, second: EntityState },
+    Fall { generation: usize, iteration: usize, entity: EntityState },
+    Goal { generation: usize, iteration: usize, entity: EntityState },
+    Birth { generation: usize, parents: (Uuid, Uuid), child: Uuid, killer: bool, values: Vec<f64> },
+    Mutation { generation: usize, entity: Uuid, mutation: Mutation },
+    GenerationEnd(GenerationStats),
+}
+
+/// Identifier, position and killer flag of an entity
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct EntityState {
+    pub id: Uuid,
+    pub position: Option<Point>,
+    pub killer: bool,
+}
+
+impl From<&Entity> for EntityState {
+
+    fn from(entity: &Entity) -> Self {
+        EntityState { id: entity.id, position: entity.position, killer: entity.killer }
+    }
+}
+
+/// EventLog struct
+///
+/// Observer that writes every simulation event as a JSON line
+///
+/// Births and mutations are recorded with the generation of the
+/// parents. Observer callbacks can't fail, so the first write error
+/// is kept and returned by `finish`
+
+pub struct EventLog<W: Write> {
+    writer: W,
+    generation: usize,
+    iteration: usize,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> EventLog<W> {
+
+    pub fn new(writer: W) -> Self {
+        EventLog { writer, generation: 0, iteration: 0, error: None }
+    }
+
+    /// Write a record, nothing is written after the first error
+
+    pub fn record(&mut self, event: &Event) {
+
+        if self.error.is_some() {
+            return
+        }
+
+        let result = serde_json::to_writer(&mut self.writer, event)
+            .map_err(io::Error::from)
+            .and_then(|_| self.writer.write_all(b"\n"));
+
+        if let Err(error) = result {
+            self.error = Some(error);
+        }
+    }
+
+    /// Flush the writer and return the first error found
+
+    pub fn finish(mut self) -> io::Result<W> {
+
+        if let Some(error) = self.error.take() {
+            return Err(error)
+        }
+
+        self.writer.flush()?;
+
+        Ok(self.writer)
+    }
+}
+
+impl<W: Write> SimulationObserver for EventLog<W> {
+
+    fn on_generation_start(&mut self, generation: usize, entities: &[Entity]) {
+
+        self.generation = generation;
+        self.iteration = 0;
+
+        let entities = entities.iter().map(EntityState::from).collect();
+
+        self.record(&Event::GenerationStart { generation, entities });
+    }
+
+    fn on_iteration_start(&mut self, _generation: usize, iteration: usize) {
+        self.iteration = iteration;
+    }
+
+    fn on_move(&mut self, entity: &Entity, from: Point, to: Point) {
+        let (generation, iteration) = (self.generation, self.iteration);
+        self.record(&Event::Move { generation, iteration, entity: entity.id, from, to });
+    }
+
+    fn on_kill(&mut self, killer: &Entity, victim: &Entity) {
+        let (generation, iteration) = (self.generation, self.iteration);
+        self.record(&Event::Kill { generation, iteration, killer: killer.into(), victim: victim.into() });
+    }
+
+    fn on_mutual_kill(&mut self, first: &Entity, second: &Entity) {
+        let (generation, iteration) = (self.generation, self.iteration);
+        self.record(&Event::MutualKill { generation, iteration, first: first.into(), second: second.into() });
+    }
+
+    fn on_fall(&mut self, entity: &Entity) {
+        let (generation, iteration) = (self.generation, self.iteration);
+        self.record(&Event::Fall { generation, iteration, entity: entity.into() });
+    }
+
+    fn on_goal_reached(&mut self, entity: &Entity) {
+        let (generation, iteration) = (self.generation, self.iteration);
+        self.record(&Event::Goal { generation, iteration, entity: entity.into() });
+    }
+
+    fn on_birth(&mut self, parents: (&Entity, &Entity), child: &Entity) {
+        self.record(&Event::Birth {
+            generation: self.generation,
+            parents: (parents.0.id, parents.1.id),
+            child: child.id,
+            killer: child.killer,
+            values: child.values.clone(),
+        });
+    }
+
+    fn on_mutation(&mut self, entity: &Entity, mutation: Mutation) {
+        self.record(&Event::Mutation { generation: self.generation, entity: entity.id, mutation });
+    }
+
+    fn on_generation_end(&mut self, stats: &GenerationStats) {
+        self.record(&Event::GenerationEnd(stats.clone()));
+    }
+}
@@ -0,0 +1,119 @@
+use std::path::Path;
+use std::io::{self, Write};
+
+use serde::{Deserialize, Serialize};
+
+use crate::utils;
+use crate::random;
+use crate::stats::GenerationStats;
+use crate::config::SimulationConfig;
+
+/// RunMetadata struct
+///
+/// Description of a finished run written next to its statistics
+///
+/// # Attributes
+///
+/// * `seed` - Seed of the run as a hex string
+/// * `config` - Resolved simulation configuration
+/// * `started_at` - Start of the run in seconds since the unix epoch
+/// * `wall_clock_seconds` - Duration of the run
+/// * `converged` - Whether a generation converged
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RunMetadata {
+    pub seed: String,
+    pub config: SimulationConfig,
+    pub started_at: u64,
+    pub wall_clock_seconds: f64,
+    pub converged: bool,
+}
+
+impl RunMetadata {
+
+    pub fn new(config: &SimulationConfig, started_at: u64, wall_clock_seconds: f64, converged: bool) -> Self {
+        RunMetadata {
+            seed: config.seed.map(random::format_seed).unwrap_or_default(),
+            config: config.clone(),
+            started_at,
+            wall_clock_seconds,
+            converged,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct StatsFile<'a> {
+    metadata: &'a RunMetadata,
+    generations: &'a [GenerationStats],
+}
+
+/// Write the statistics as a JSON object with the
+/// `metadata` and the list of `generations`
+
+pub fn write_json<W: Write>(writer: W, metadata: &RunMetadata, generations: &[GenerationStats]) -> io::Result<()> {
+    serde_json::to_writer_pretty(writer, &StatsFile { metadata, generations })?;
+    Ok(())
+}
+
+/// Write the statistics as CSV, one row per generation
+///
+/// The metadata goes first as `#` comment lines, the per direction
+/// values are expanded as `gene_mean_<i>` and `gene_variance_<i>`
+/// columns and missing values are left empty
+
+pub fn write_csv<W: Write>(mut writer: W, metadata: &RunMetadata, generations: &[GenerationStats]) -> io::Result<()> {
+
+    writeln!(writer, "# seed: {}", metadata.seed)?;
+    writeln!(writer, "# started_at: {}", metadata.started_at)?;
+    writeln!(writer, "# wall_clock_seconds: {}", metadata.wall_clock_seconds)?;
+    writeln!(writer, "# converged: {}", metadata.converged)?;
+
+    for line in metadata.config.to_toml().lines() {
+        writeln!(writer, "# config: {}", line)?;
+    }
+
+    let genes = generations.first().map(|g| g.gene_means.len()).unwrap_or(0);
+
+    let mut header: Vec<String> = ["generation", "sample", "murders", "winners", "converged"]
+        .iter().map(|h| h.to_string()).collect();
+
+    header.extend(GenerationStats::SERIES[2..].iter().map(|h| h.to_string()));
+    header.extend((0..genes).map(|i| format!("gene_mean_{}", i)));
+    header.extend((0..genes).map(|i| format!("gene_variance_{}", i)));
+
+    writeln!(writer, "{}", header.join(","))?;
+
+    for stats in generations.iter() {
+
+        let mut row = vec![
+            stats.generation.to_string(),
+            stats.sample.to_string(),
+            stats.murders.to_string(),
+            stats.winners.to_string(),
+            stats.converged.to_string(),
+        ];
+
+        for key in GenerationStats::SERIES[2..].iter() {
+            row.push(stats.value(key).map(|v| v.to_string()).unwrap_or_default());
+        }
+
+        row.extend(stats.gene_means.iter().map(|v| v.to_string()));
+        row.extend(stats.gene_variances.iter().map(|v| v.to_string()));
+
+        writeln!(writer, "{}", row.join(","))?;
+    }
+
+    Ok(())
+}
+
+/// Write the statistics to a file, CSV if the extension
+/// is `.csv` and JSON otherwise
+
+pub fn write_stats(path: &Path, metadata: &RunMetadata, generations: &[GenerationStats]) -> io::Result<()> {
+
+    utils::replace(path, |writer| match path.extension().and_then(|ext| ext.to_str()) {
+        Some("csv") => write_csv(writer, metadata, generations),
+        _ => write_json(writer, metadata, generations),
+    })
+}
@@ -0,0 +1,153 @@
+use colored::*;
+
+use crate::board::Board;
+use crate::entity::Entity;
+use crate::position::Point;
+use crate::occupancy::Occupancy;
+use crate::topology::Topology;
+
+/// Cell struct
+///
+/// Alive entity drawn on a board cell
+///
+/// # Attributes
+///
+/// * `position` - Cell of the entity
+/// * `killer` - Entity killer flag
+/// * `color` - Entity color
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Cell {
+    pub position: Point,
+    pub killer: bool,
+    pub color: CustomColor,
+}
+
+/// Frame struct
+///
+/// What is drawn of the board after an iteration, it's built from the
+/// entities of a running Poblation or from a replay file
+///
+/// # Attributes
+///
+/// * `generation` - Generation of the frame
+/// * `iteration` - Iteration of the frame (0 is the initial state)
+/// * `board` - Board of the simulation, with its walls
+/// * `cells` - Alive entities
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Frame {
+    pub generation: usize,
+    pub iteration: usize,
+    pub board: Board,
+    pub cells: Vec<Cell>,
+}
+
+impl Frame {
+
+    /// Create the frame of the alive entities
+    ///
+    /// # Arguments
+    ///
+    /// * `generation` - Generation of the frame
+    /// * `iteration` - Iteration of the frame
+    /// * `board` - Board of the simulation
+    /// * `entities` - Entities of the Poblation
+
+    pub fn new(generation: usize, iteration: usize, board: &Board, entities: &[Entity]) -> Self {
+
+        let cells = entities.iter()
+            .filter(|e| e.alive)
+            .filter_map(|e| e.position.map(|position| Cell { position, killer: e.killer, color: e.color }))
+            .collect();
+
+        Frame { generation, iteration, board: board.clone(), cells }
+    }
+
+    /// Index of the cells of the frame, by their place in `cells`
+
+    pub fn occupancy(&self) -> Occupancy {
+
+        let mut occupancy = Occupancy::new();
+
+        for (index, cell) in self.cells.iter().enumerate() {
+            occupancy.insert(cell.position, index);
+        }
+
+        occupancy
+    }
+
+    /// Draw the board with ANSI colors, the screen is cleared first
+    ///
+    /// Every cell is 6 characters wide and 3 lines high, killers
+    /// have a white stripe in the middle and walls are filled with `#`.
+    /// On a hex board the odd rows are shifted half a cell to the right
+
+    pub fn render(&self) -> String {
+
+        let (rows, cols) = self.board.dimensions;
+        let occupancy = self.occupancy();
+
+        let mut buffer = String::new();
+
+        let total_width = match self.board.topology {
+            Topology::Hex => cols * 7 + 3,
+            _ => cols * 7,
+        };
+
+        buffer.push_str("\x1B[2J\x1B[1;1H");
+        buffer.push_str(&format!("+{:-<1$}+\n", "", total_width));
+
+        let header = format!("| Generation: {:<5} Movement: {:<5} {}|", self.generation, self.iteration, " ".repeat(total_width.saturating_sub(36)));
+        buffer.push_str(&format!("{}\n", header));
+        buffer.push_str(&format!("+{:-<1$}+\n", "", total_width));
+
+        for y in 0..rows {
+
+            let shifted = self.board.is_shifted(y);
+
+            for _ in 0..3 {
+
+                buffer.push('|');
+
+                if shifted {
+                    buffer.push_str("   ");
+                }
+
+                for x in 0..cols {
+
+                    if let Some(c) = occupancy.get(Point::new(x as isize, y as isize)).map(|i| &self.cells[i]) {
+
+                        if c.killer {
+                            buffer.push_str(&(0..2).map(|_| "*".custom_color(c.color).to_string()).collect::<String>());
+                            buffer.push_str(&(0..2).map(|_| "*".white().to_string()).collect::<String>());
+                            buffer.push_str(&format!("{}|", (0..2).map(|_| "*".custom_color(c.color).to_string()).collect::<String>()));
+                        } else {
+                            buffer.push_str(&format!("{}|", (0..6).map(|_| "*".custom_color(c.color).to_string()).collect::<String>()));
+                        }
+
+                    } else if self.board.is_wall(Point::new(x as isize, y as isize)) {
+                        buffer.push_str(&format!("{}|", "#".repeat(6).bright_black()));
+
+                    } else {
+                        buffer.push_str(&format!(" {}|", " ".repeat(5)));
+                    }
+                }
+
+                if self.board.topology == Topology::Hex && !shifted {
+                    buffer.push_str("  |");
+                }
+
+                buffer.push('\n');
+            }
+
+            if y < rows - 1 {
+                buffer.push_str(&format!("+{:-<1$}+\n", "", total_width));
+            }
+        }
+
+        buffer.push_str(&format!("+{:-<1$}+\n", "", total_width));
+
+        buffer
+    }
+}
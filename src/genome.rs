@@ -0,0 +1,168 @@
+use std::fs::File;
+use std::path::Path;
+use std::io::{self, BufReader};
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::utils;
+use crate::entity::Entity;
+use crate::position::Position;
+use crate::topology::Topology;
+use crate::config::{ConfigError, SimulationConfig};
+use crate::observer::SimulationObserver;
+
+/// Genome struct
+///
+/// Inheritable part of an entity, it doesn't depend on
+/// the board so it can be used on any grid size
+///
+/// # Attributes
+///
+/// * `values` - Direction weights
+/// * `killer` - Killer gene
+/// * `fitness` - Fitness the entity had when it reached the goal
+/// * `generation` - Generation where the entity reached the goal
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Genome {
+    pub values: Vec<f64>,
+    pub killer: bool,
+    pub fitness: usize,
+    pub generation: usize,
+}
+
+impl Genome {
+
+    pub fn from_entity(entity: &Entity, generation: usize) -> Self {
+        Genome {
+            values: entity.values.clone(),
+            killer: entity.killer,
+            fitness: entity.fitness,
+            generation,
+        }
+    }
+
+    /// Create a new Entity with this genome
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - Entity position
+    /// * `config` - Simulation configuration
+    /// * `rng` - Random generator used for the identifier
+
+    pub fn entity<R: Rng>(&self, position: Position, config: &SimulationConfig, rng: &mut R) -> Entity {
+
+        let values = utils::normalize(&self.values);
+        let color = utils::values_color(&values);
+
+        let mut entity = Entity::from(values, self.killer, position, color, rng);
+        entity.fitness = config.n_iterations;
+
+        entity
+    }
+
+    /// Check that the genome can be used by an Entity: one
+    /// non-negative weight per move of the topology, not all zero
+
+    pub fn is_valid(&self, topology: Topology) -> bool {
+        self.values.len() == topology.genes()
+            && self.values.iter().all(|v| v.is_finite() && *v >= 0.0)
+            && self.values.iter().sum::<f64>() > 0.0
+    }
+}
+
+/// HallOfFame struct
+///
+/// Observer that keeps the best genomes that reached the goal
+/// across every generation, sorted from the highest fitness
+///
+/// # Attributes
+///
+/// * `capacity` - Maximum number of genomes kept
+/// * `genomes` - Best genomes found
+///
+/// # Examples
+///
+/// ```
+/// use genetic::SimulationBuilder;
+/// use genetic::genome::HallOfFame;
+///
+/// let mut hall_of_fame = HallOfFame::new(4);
+///
+/// let mut poblation = SimulationBuilder::new().seed(42).generations(20).headless(true).build().unwrap();
+/// poblation.run_with(&mut hall_of_fame);
+///
+/// let transferred = SimulationBuilder::new()
+///     .seed(7)
+///     .dimensions(20, 30)
+///     .genomes(hall_of_fame.genomes)
+///     .build()
+///     .unwrap();
+/// ```
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct HallOfFame {
+    pub capacity: usize,
+    pub genomes: Vec<Genome>,
+    #[serde(skip)]
+    generation: usize,
+}
+
+impl HallOfFame {
+
+    pub fn new(capacity: usize) -> Self {
+        HallOfFame { capacity, genomes: Vec::new(), generation: 0 }
+    }
+
+    /// Add a genome if it's better than the worst one kept,
+    /// repeated genomes are only kept once
+
+    pub fn insert(&mut self, genome: Genome) {
+
+        if self.genomes.iter().any(|g| g.values == genome.values && g.killer == genome.killer) {
+            return
+        }
+
+        // Con igual fitness se mantiene el más antiguo
+
+        let index = self.genomes.partition_point(|g| g.fitness >= genome.fitness);
+
+        if index < self.capacity {
+            self.genomes.insert(index, genome);
+            self.genomes.truncate(self.capacity);
+        }
+    }
+
+    /// Write the genomes as JSON
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        utils::replace(path, |writer| Ok(serde_json::to_writer_pretty(writer, self)?))
+    }
+
+    /// Read a genome file, every genome must be valid (`Genome::is_valid`)
+    /// for the topology given by its number of values
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+
+        let reader = BufReader::new(File::open(path)?);
+        let hall_of_fame: HallOfFame = serde_json::from_reader(reader)?;
+
+        if let Some(index) = hall_of_fame.genomes.iter().position(|g| !Topology::from_genes(g.values.len()).is_some_and(|t| g.is_valid(t))) {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, ConfigError::Genome(index)))
+        }
+
+        Ok(hall_of_fame)
+    }
+}
+
+impl SimulationObserver for HallOfFame {
+
+    fn on_generation_start(&mut self, generation: usize, _entities: &[Entity]) {
+        self.generation = generation;
+    }
+
+    fn on_goal_reached(&mut self, entity: &Entity) {
+        self.insert(Genome::from_entity(entity, self.generation));
+    }
+}
@@ -0,0 +1,44 @@
+#![allow(clippy::empty_line_after_doc_comments)]
+
+//! Genetic algorithm that evolves entities to cross a board
+//! 
+//! The simulation is a `Poblation` created from a `SimulationConfig`,
+//! usually through `SimulationBuilder`. It can be run until it converges
+//! (`Poblation::run`), one generation at a time (`Poblation::step_generation`)
+//! or one movement at a time (`Poblation::step_iteration` followed by
+//! `Poblation::finish_generation`), and the per-generation statistics are
+//! read with `Poblation::statistics`.
+//! 
+//! Every driving method has a `*_with` variant that notifies a
+//! `SimulationObserver` of moves, murders, arrivals and selections.
+
+pub mod board;
+pub mod stats;
+pub mod utils;
+pub mod config;
+pub mod entity;
+pub mod map;
+pub mod occupancy;
+pub mod genome;
+pub mod events;
+pub mod frame;
+pub mod export;
+pub mod random;
+pub mod render;
+pub mod replay;
+pub mod report;
+pub mod builder;
+pub mod chart;
+pub mod checkpoint;
+pub mod observer;
+pub mod position;
+pub mod topology;
+pub mod poblation;
+
+pub use entity::Entity;
+pub use poblation::Poblation;
+pub use stats::GenerationStats;
+pub use observer::SimulationObserver;
+pub use position::{Point, Position};
+pub use builder::SimulationBuilder;
+pub use config::{ConfigError, SimulationConfig};
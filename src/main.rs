@@ -1,35 +1,361 @@
+#![allow(clippy::empty_line_after_doc_comments)]
 
-pub mod utils;
-pub mod entity;
-pub mod random;
-pub mod position;
-pub mod poblation;
+mod cli;
+mod outputs;
+mod tui;
+mod viewer;
 
-use poblation::Poblation;
-use utils::continue_prompt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::exit;
 
-pub mod constants {
+use clap::Parser;
+use cli::{Cli, Command, ImageFormat, OutputArgs, SimulationArgs};
+use outputs::Outputs;
+use tui::Tui;
+use viewer::Viewer;
 
-    pub const SAMPLE: usize = 16;
-    pub const N_ITERATIONS: usize = 100;
-    pub const DIMENSIONS: (usize, usize) = (12, 20);
-    pub const N_GENERATIONS: usize = 500;
-    pub const SHOW_THRESHOLD: usize = 10;
-    pub const SEED: [u8; 32] = [10; 32];
-    pub const MUTATION_PROBABILTY: f64 = 0.05;
-    pub const KILLER_PROBABILITY: f64 = 0.2;
-    pub const P: f64 = 0.5;
+use genetic::random;
+use genetic::render;
+use genetic::chart::{self, ChartOptions};
+use genetic::map::{self, Map};
+use genetic::board::Board;
+use genetic::replay::Replay;
+use genetic::checkpoint::Checkpoint;
+use genetic::utils::continue_prompt;
+use genetic::{Poblation, SimulationConfig};
+
+/// Build the Poblation from the config file and the
+/// command line flags, exiting if the result is invalid
+/// 
+/// The resolved config, with the changes of the subcommand (`customize`),
+/// is echoed and written to `--out-dir`, unseeded runs get a new seed
+/// here so it's always recorded
+
+fn build(args: &SimulationArgs, customize: impl FnOnce(&mut SimulationConfig)) -> Poblation {
+
+    let mut config = args.resolve().and_then(|config| {
+        config.validate()?;
+        Ok(config)
+    }).unwrap_or_else(|error| {
+        eprintln!("error: invalid configuration: {}", error);
+        exit(1)
+    });
+
+    let seed = *config.seed.get_or_insert_with(random::entropy_seed);
+
+    customize(&mut config);
+
+    println!("{}", config.to_toml());
+
+    if let Some(dir) = &args.outputs.out_dir {
+        if let Err(error) = config.write_to(dir) {
+            eprintln!("error: {}", error);
+            exit(1)
+        }
+    }
+
+    Poblation::with_rng(config, random::generator(seed))
+}
+
+/// Create the output files requested by the flags, exiting on error
+
+fn open_outputs(args: &OutputArgs, config: &SimulationConfig) -> Outputs {
+    Outputs::open(args, config).unwrap_or_else(|error| {
+        eprintln!("error: can't create output file: {}", error);
+        exit(1)
+    })
+}
+
+/// Run the remaining generations saving the checkpoints, it
+/// matches `Poblation::run_with`
+///
+/// # Returns
+///
+/// * `bool` - Whether a generation converged
+
+fn drive(poblation: &mut Poblation, outputs: &mut Outputs) -> bool {
+
+    while poblation.actual_gen <= poblation.config.n_generations {
+
+        if poblation.step_generation_with(&mut outputs.observers()) {
+            return true
+        }
+
+        if let Err(error) = outputs.checkpoint(poblation) {
+            eprintln!("error: can't write checkpoint: {}", error);
+            exit(1)
+        }
+    }
+
+    false
+}
+
+/// Write the output files of the finished run, exiting on error
+
+fn close_outputs(outputs: Outputs, poblation: &Poblation, converged: bool) {
+    if let Err(error) = outputs.finish(poblation, converged) {
+        eprintln!("error: can't write output file: {}", error);
+        exit(1)
+    }
+}
+
+/// Load a replay file, exiting if it can't be read or it's empty
+
+fn load_replay(path: &Path) -> Replay {
+
+    let replay = Replay::load(path).unwrap_or_else(|error| {
+        eprintln!("error: can't read replay {}: {}", path.display(), error);
+        exit(1)
+    });
+
+    if replay.generations.is_empty() {
+        eprintln!("error: {} has no generations", path.display());
+        exit(1)
+    }
+
+    replay
+}
+
+/// Write the images of the recorded generations of a replay
+/// 
+/// # Arguments
+/// 
+/// * `replay` - Replay with the frames
+/// * `dir` - Directory of the images, it's created if it doesn't exist
+/// * `format` - Image format, GIF files hold every iteration of a generation
+/// * `generation` - Only this generation, if given
+/// * `iteration` - Only this iteration, if given
+/// * `frame_delay` - Delay between GIF frames in milliseconds
+/// 
+/// # Returns
+/// 
+/// * `io::Result<Vec<PathBuf>>` - Written files
+
+fn render_replay(replay: &Replay, dir: &Path, format: ImageFormat, generation: Option<usize>,
+    iteration: Option<usize>, frame_delay: u64) -> io::Result<Vec<PathBuf>> {
+
+    std::fs::create_dir_all(dir)?;
+
+    let mut written = Vec::new();
+
+    let generations = replay.generations.iter()
+        .filter(|g| generation.is_none_or(|n| g.generation == n));
+
+    for recorded in generations {
+
+        let frames: Vec<_> = recorded.frames(&Board::new(&replay.config)).into_iter()
+            .filter(|f| iteration.is_none_or(|i| f.iteration == i))
+            .collect();
+
+        if frames.is_empty() {
+            continue
+        }
+
+        if format == ImageFormat::Gif {
+            let path = dir.join(format!("generation_{:04}.gif", recorded.generation));
+            render::write_gif(&path, &frames, frame_delay)?;
+            written.push(path);
+            continue
+        }
+
+        for frame in frames.iter() {
+
+            let name = format!("generation_{:04}_iteration_{:03}", frame.generation, frame.iteration);
+
+            let path = match format {
+                ImageFormat::Svg => {
+                    let path = dir.join(name + ".svg");
+                    render::write_svg(&path, frame)?;
+                    path
+                },
+                _ => {
+                    let path = dir.join(name + ".png");
+                    render::write_png(&path, frame)?;
+                    path
+                },
+            };
+
+            written.push(path);
+        }
+    }
+
+    Ok(written)
+}
+
+/// Print the outcome of a finished simulation
+
+fn summary(poblation: &Poblation, converged: bool) {
+
+    let generations = poblation.actual_gen.min(poblation.config.n_generations);
+
+    match converged {
+        true => println!("Converged on generation {}", generations),
+        false => println!("Didn't converge in {} generations", generations),
+    }
+
+    // Un checkpoint de una ejecución con un generador propio (SimulationBuilder::rng) no tiene semilla
+
+    if let Some(seed) = poblation.config.seed {
+        println!("Seed: {}", random::format_seed(seed));
+    }
 }
 
 fn main() {
 
-    let mut poblation = Poblation::new();
+    let cli = Cli::parse();
+
+    match cli.command {
+
+        Command::Run(args) => {
+
+            let mut poblation = build(&args, |_| {});
+            let mut outputs = open_outputs(&args.outputs, &poblation.config);
+
+            // Sin terminal (o sin tablero) se usa Poblation::show
+
+            let tui = match poblation.config.headless {
+                true => None,
+                false => Tui::open(poblation.config.frame_delay),
+            };
+
+            let converged = match tui {
+                Some(tui) => tui.run(&mut poblation, &mut outputs).unwrap_or_else(|error| {
+                    eprintln!("error: can't write checkpoint: {}", error);
+                    exit(1)
+                }),
+                None => drive(&mut poblation, &mut outputs),
+            };
+
+            close_outputs(outputs, &poblation, converged);
+
+            // La pantalla se limpia en cada frame, se repite la semilla al terminar
+
+            summary(&poblation, converged);
+
+            if poblation.config.headless {
+                return
+            }
+
+            continue_prompt();
+
+            poblation.graphic("murders");
+            continue_prompt();
+
+            poblation.graphic("winners");
+        },
+
+        Command::Debug(args) => {
+            let mut poblation = build(&args, |config| config.headless = true);
+            let mut outputs = open_outputs(&args.outputs, &poblation.config);
+
+            poblation.run_debug_with(&mut outputs.observers());
+            close_outputs(outputs, &poblation, false);
+        },
+
+        Command::Replay { file, generation, frame_delay } => {
+
+            let replay = load_replay(&file);
+
+            let generation = generation.unwrap_or(replay.generations[0].generation);
+            let frame_delay = frame_delay.unwrap_or(replay.config.frame_delay);
+
+            Viewer::new(&replay, generation, frame_delay).play();
+        },
+
+        Command::Plot { simulation, series, chart_out, moving_average, title } => {
+
+            let mut poblation = build(&simulation, |config| config.headless = true);
+            let mut outputs = open_outputs(&simulation.outputs, &poblation.config);
+
+            let converged = drive(&mut poblation, &mut outputs);
+            close_outputs(outputs, &poblation, converged);
+
+            let Some(path) = chart_out else {
+
+                for serie in series {
+                    poblation.graphic(&serie);
+                }
+
+                return
+            };
+
+            let options = ChartOptions { title, moving_average, ..ChartOptions::default() };
+
+            if let Err(error) = chart::write_chart(&path, &poblation.generations, &series, &options) {
+                eprintln!("error: can't write chart {}: {}", path.display(), error);
+                exit(1)
+            }
+
+            println!("{}", path.display());
+        },
+
+        Command::Render { file, out_dir, format, generation, iteration, frame_delay } => {
+
+            let replay = load_replay(&file);
+            let frame_delay = frame_delay.unwrap_or(replay.config.frame_delay);
+
+            let written = render_replay(&replay, &out_dir, format, generation, iteration, frame_delay).unwrap_or_else(|error| {
+                eprintln!("error: can't write image: {}", error);
+                exit(1)
+            });
+
+            if written.is_empty() {
+                eprintln!("error: no recorded frame matches the given generation and iteration");
+                exit(1)
+            }
+
+            for path in written {
+                println!("{}", path.display());
+            }
+        },
+
+        Command::Maps => {
+
+            for (name, text) in map::BUNDLED {
+
+                let (rows, cols) = Map::parse(text).unwrap().dimensions;
+
+                println!("{} ({}x{})\n{}", name, rows, cols, text);
+            }
+        },
+
+        Command::Resume { file, outputs: args, frame_delay, headless } => {
+
+            let mut checkpoint = Checkpoint::load(&file).unwrap_or_else(|error| {
+                eprintln!("error: can't read checkpoint {}: {}", file.display(), error);
+                exit(1)
+            });
+
+            // El salón de la fama sigue en los archivos de esta ejecución
+
+            let hall_of_fame = checkpoint.hall_of_fame.take();
+            let mut poblation = checkpoint.restore();
+
+            if let Some(delay) = frame_delay {
+                poblation.config.frame_delay = delay;
+            }
+
+            if headless {
+                poblation.config.headless = true;
+            }
+
+            if let Some(dir) = &args.out_dir {
+                if let Err(error) = poblation.config.write_to(dir) {
+                    eprintln!("error: {}", error);
+                    exit(1)
+                }
+            }
+
+            println!("Resuming from generation {}", poblation.actual_gen);
+
+            let mut outputs = open_outputs(&args, &poblation.config);
+            outputs.resume(hall_of_fame);
+
+            let converged = drive(&mut poblation, &mut outputs);
+            close_outputs(outputs, &poblation, converged);
 
-    poblation.run();
-    continue_prompt();
-    
-    poblation.graphic("murders");
-    continue_prompt();
-    
-    poblation.graphic("winners");
+            summary(&poblation, converged);
+        },
+    }
 }
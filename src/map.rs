@@ -0,0 +1,133 @@
+use std::fs;
+use std::path::Path;
+
+use crate::position::Point;
+use crate::config::ConfigError;
+
+/// Maps shipped with the simulator, selected by name
+pub const BUNDLED: [(&str, &str); 4] = [
+    ("open", include_str!("../maps/open.txt")),
+    ("wall", include_str!("../maps/wall.txt")),
+    ("funnel", include_str!("../maps/funnel.txt")),
+    ("maze", include_str!("../maps/maze.txt")),
+];
+
+/// Map struct
+///
+/// Board layout read from an ASCII map, one line per row:
+/// `#` wall, `S` start cell, `G` goal cell and `.` floor
+///
+/// # Attributes
+///
+/// * `dimensions` - Board dimensions (rows, columns)
+/// * `walls` - Impassable cells
+/// * `starts` - Cells where the entities are placed
+/// * `goals` - Cells the entities must reach
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Map {
+    pub dimensions: (usize, usize),
+    pub walls: Vec<Point>,
+    pub starts: Vec<Point>,
+    pub goals: Vec<Point>,
+}
+
+impl Map {
+
+    /// Layout used when there's no map: the first two
+    /// columns are the start and the last one is the goal
+    ///
+    /// # Arguments
+    ///
+    /// * `dimensions` - Board dimensions (rows, columns)
+
+    pub fn open(dimensions: (usize, usize)) -> Self {
+
+        let (rows, cols) = dimensions;
+
+        let column = |x: usize| (0..rows).map(move |y| Point::new(x as isize, y as isize));
+
+        Map {
+            dimensions,
+            walls: Vec::new(),
+            starts: column(0).chain(column(1)).collect(),
+            goals: column(cols.saturating_sub(1)).collect(),
+        }
+    }
+
+    /// Parse an ASCII map, blank lines around the map and the
+    /// spaces around each row are ignored
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Map, ConfigError>` - The map, or the first invalid row
+
+    pub fn parse(text: &str) -> Result<Self, ConfigError> {
+
+        let lines: Vec<(usize, &str)> = text.lines()
+            .map(str::trim)
+            .enumerate()
+            .skip_while(|(_, line)| line.is_empty())
+            .collect();
+
+        let end = lines.iter().rposition(|(_, line)| !line.is_empty()).map_or(0, |i| i + 1);
+        let lines = &lines[..end];
+
+        let Some(&(_, first)) = lines.first() else {
+            return Err(ConfigError::Map("the map is empty".to_string()))
+        };
+
+        let cols = first.chars().count();
+
+        let mut map = Map {
+            dimensions: (lines.len(), cols),
+            walls: Vec::new(),
+            starts: Vec::new(),
+            goals: Vec::new(),
+        };
+
+        for (y, &(number, line)) in lines.iter().enumerate() {
+
+            if line.chars().count() != cols {
+                return Err(ConfigError::Map(format!("line {} has {} cells, the first row has {}", number + 1, line.chars().count(), cols)))
+            }
+
+            for (x, cell) in line.chars().enumerate() {
+
+                let point = Point::new(x as isize, y as isize);
+
+                match cell {
+                    '#' => map.walls.push(point),
+                    'S' => map.starts.push(point),
+                    'G' => map.goals.push(point),
+                    '.' => {},
+                    _ => return Err(ConfigError::Map(format!("line {} has an unknown cell '{}'", number + 1, cell))),
+                }
+            }
+        }
+
+        Ok(map)
+    }
+
+    /// Get the text of a bundled map by its name
+
+    pub fn bundled(name: &str) -> Option<&'static str> {
+        BUNDLED.iter().find(|(n, _)| *n == name).map(|(_, text)| *text)
+    }
+
+    /// Read a map, a bundled one if `source` is one of their
+    /// names or otherwise the file at that path
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String, ConfigError>` - Text of the map, not parsed yet
+
+    pub fn load(source: &str) -> Result<String, ConfigError> {
+
+        if let Some(text) = Map::bundled(source) {
+            return Ok(text.to_string())
+        }
+
+        fs::read_to_string(source).map_err(|e| ConfigError::File { path: Path::new(source).to_path_buf(), message: e.to_string() })
+    }
+}
@@ -0,0 +1,106 @@
+use crate::entity::{Entity, Mutation};
+use crate::position::Point;
+use crate::stats::GenerationStats;
+
+/// SimulationObserver trait
+/// 
+/// Callbacks invoked by the Poblation while it runs, every
+/// method does nothing by default so observers only implement
+/// the events they care about
+/// 
+/// # Events
+/// 
+/// * `on_generation_start` - Before the first iteration of a generation
+/// * `on_iteration_start` - Before the entities of an iteration move
+/// * `on_move` - An entity moved to a free cell
+/// * `on_kill` - A killer killed a non killer entity
+/// * `on_mutual_kill` - Two killers met and killed each other
+/// * `on_fall` - An entity fell off a lethal edge of the board
+/// * `on_goal_reached` - An entity reached the goal
+/// * `on_birth` - A child was created by crossover
+/// * `on_mutation` - A child mutated after its birth
+/// * `on_selection` - The children of the next generation were created
+/// * `on_generation_end` - A generation finished
+
+pub trait SimulationObserver {
+
+    fn on_generation_start(&mut self, _generation: usize, _entities: &[Entity]) {}
+
+    fn on_iteration_start(&mut self, _generation: usize, _iteration: usize) {}
+
+    fn on_move(&mut self, _entity: &Entity, _from: Point, _to: Point) {}
+
+    fn on_kill(&mut self, _killer: &Entity, _victim: &Entity) {}
+
+    fn on_mutual_kill(&mut self, _first: &Entity, _second: &Entity) {}
+
+    fn on_fall(&mut self, _entity: &Entity) {}
+
+    fn on_goal_reached(&mut self, _entity: &Entity) {}
+
+    fn on_birth(&mut self, _parents: (&Entity, &Entity), _child: &Entity) {}
+
+    fn on_mutation(&mut self, _entity: &Entity, _mutation: Mutation) {}
+
+    /// # Arguments
+    /// 
+    /// * `parents` - Parents pool ordered by selection probability
+    /// * `children` - Entities of the next generation
+
+    fn on_selection(&mut self, _parents: &[Entity], _children: &[Entity]) {}
+
+    fn on_generation_end(&mut self, _stats: &GenerationStats) {}
+}
+
+/// The unit observer ignores every event
+
+impl SimulationObserver for () {}
+
+/// Forward every event to a list of observers, in order
+
+impl SimulationObserver for Vec<&mut dyn SimulationObserver> {
+
+    fn on_generation_start(&mut self, generation: usize, entities: &[Entity]) {
+        self.iter_mut().for_each(|o| o.on_generation_start(generation, entities))
+    }
+
+    fn on_iteration_start(&mut self, generation: usize, iteration: usize) {
+        self.iter_mut().for_each(|o| o.on_iteration_start(generation, iteration))
+    }
+
+    fn on_move(&mut self, entity: &Entity, from: Point, to: Point) {
+        self.iter_mut().for_each(|o| o.on_move(entity, from, to))
+    }
+
+    fn on_kill(&mut self, killer: &Entity, victim: &Entity) {
+        self.iter_mut().for_each(|o| o.on_kill(killer, victim))
+    }
+
+    fn on_mutual_kill(&mut self, first: &Entity, second: &Entity) {
+        self.iter_mut().for_each(|o| o.on_mutual_kill(first, second))
+    }
+
+    fn on_fall(&mut self, entity: &Entity) {
+        self.iter_mut().for_each(|o| o.on_fall(entity))
+    }
+
+    fn on_goal_reached(&mut self, entity: &Entity) {
+        self.iter_mut().for_each(|o| o.on_goal_reached(entity))
+    }
+
+    fn on_birth(&mut self, parents: (&Entity, &Entity), child: &Entity) {
+        self.iter_mut().for_each(|o| o.on_birth(parents, child))
+    }
+
+    fn on_mutation(&mut self, entity: &Entity, mutation: Mutation) {
+        self.iter_mut().for_each(|o| o.on_mutation(entity, mutation))
+    }
+
+    fn on_selection(&mut self, parents: &[Entity], children: &[Entity]) {
+        self.iter_mut().for_each(|o| o.on_selection(parents, children))
+    }
+
+    fn on_generation_end(&mut self, stats: &GenerationStats) {
+        self.iter_mut().for_each(|o| o.on_generation_end(stats))
+    }
+}
@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+
+use crate::entity::Entity;
+use crate::position::Point;
+
+/// Occupancy struct
+///
+/// Index of the cells taken by alive entities, so finding the entity
+/// on a cell doesn't scan the entities vector. Only the occupied
+/// cells are stored, the size of the board doesn't matter
+///
+/// # Attributes
+///
+/// * `cells` - Index of the entity on every occupied cell
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Occupancy {
+    cells: HashMap<Point, usize>,
+}
+
+impl Occupancy {
+
+    pub fn new() -> Self {
+        Occupancy::default()
+    }
+
+    /// Create the index of the alive entities with a position
+    ///
+    /// # Arguments
+    ///
+    /// * `entities` - Entities, they're indexed by their place in the slice
+
+    pub fn from_entities(entities: &[Entity]) -> Self {
+
+        let mut occupancy = Occupancy::new();
+
+        for (index, entity) in entities.iter().enumerate() {
+            if let Some(point) = entity.position.filter(|_| entity.alive) {
+                occupancy.insert(point, index);
+            }
+        }
+
+        occupancy
+    }
+
+    /// Get the index of the entity on a cell
+
+    pub fn get(&self, point: Point) -> Option<usize> {
+        self.cells.get(&point).copied()
+    }
+
+    pub fn is_occupied(&self, point: Point) -> bool {
+        self.cells.contains_key(&point)
+    }
+
+    /// Put an entity on a cell, replacing the previous one
+
+    pub fn insert(&mut self, point: Point, index: usize) {
+        self.cells.insert(point, index);
+    }
+
+    /// Free a cell
+    ///
+    /// # Returns
+    ///
+    /// * `Option<usize>` - Index of the entity that was on the cell
+
+    pub fn remove(&mut self, point: Point) -> Option<usize> {
+        self.cells.remove(&point)
+    }
+
+    /// Move the entity of a cell to another one
+
+    pub fn relocate(&mut self, from: Point, to: Point) {
+        if let Some(index) = self.remove(from) {
+            self.insert(to, index);
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use std::fs::{File, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::io::{self, BufWriter};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use genetic::export::{self, RunMetadata};
+use genetic::events::EventLog;
+use genetic::checkpoint::Checkpoint;
+use genetic::genome::HallOfFame;
+use genetic::report::Report;
+use genetic::replay::ReplayRecorder;
+use genetic::{Poblation, SimulationConfig, SimulationObserver};
+
+use crate::cli::OutputArgs;
+
+/// Outputs struct
+/// 
+/// Files requested on the command line, the event log is written
+/// while the simulation runs and the rest when it finishes. Every
+/// path is checked when the outputs are opened, so a bad one is
+/// reported before the simulation runs, and the files written at the
+/// end replace the old ones only once they're complete
+/// 
+/// The report takes the genomes of the hall of fame and the last
+/// frame of the replay, so they're recorded even if their files
+/// weren't requested. The checkpoints also keep the hall of fame,
+/// a resumed run ends with the same genomes as an uninterrupted one
+
+pub struct Outputs {
+    events: Option<EventLog<BufWriter<File>>>,
+    stats_out: Option<PathBuf>,
+    checkpoint: Option<PathBuf>,
+    checkpoint_every: usize,
+    hall_of_fame: Option<HallOfFame>,
+    hall_of_fame_out: Option<PathBuf>,
+    replay: Option<ReplayRecorder>,
+    replay_out: Option<PathBuf>,
+    report: Option<PathBuf>,
+    started_at: SystemTime,
+    clock: Instant,
+}
+
+impl Outputs {
+
+    /// Create the output files of the given flags, the
+    /// run is timed from this point
+    /// 
+    /// # Arguments
+    /// 
+    /// * `args` - Output flags
+    /// * `config` - Configuration of the run
+
+    pub fn open(args: &OutputArgs, config: &SimulationConfig) -> io::Result<Self> {
+
+        let events = match &args.events_out {
+            Some(path) => Some(EventLog::new(BufWriter::new(create(path)?))),
+            None => None,
+        };
+
+        // Los demás archivos se escriben al terminar, se abren sin truncar para
+        // comprobar la ruta y no perder su contenido (p. ej. los genomas de `--genomes`)
+
+        for path in [&args.stats_out, &args.hall_of_fame, &args.replay_out, &args.report].into_iter().flatten() {
+            OpenOptions::new().create(true).append(true).open(path).map_err(|e| path_error(path, e))?;
+        }
+
+        Ok(Outputs {
+            events,
+            stats_out: args.stats_out.clone(),
+            checkpoint: args.checkpoint.clone(),
+            checkpoint_every: args.checkpoint_every as usize,
+            hall_of_fame: (args.hall_of_fame.is_some() || args.report.is_some() || args.checkpoint.is_some())
+                .then(|| HallOfFame::new(args.hall_of_fame_size)),
+            hall_of_fame_out: args.hall_of_fame.clone(),
+            replay: (args.replay_out.is_some() || args.report.is_some())
+                .then(|| ReplayRecorder::new(config, args.replay_generations.clone().unwrap_or_default())),
+            replay_out: args.replay_out.clone(),
+            report: args.report.clone(),
+            started_at: SystemTime::now(),
+            clock: Instant::now(),
+        })
+    }
+
+    /// Observers that must be notified by the simulation
+
+    pub fn observers(&mut self) -> Vec<&mut dyn SimulationObserver> {
+
+        let mut observers: Vec<&mut dyn SimulationObserver> = Vec::new();
+
+        if let Some(events) = &mut self.events {
+            observers.push(events);
+        }
+
+        if let Some(hall_of_fame) = &mut self.hall_of_fame {
+            observers.push(hall_of_fame);
+        }
+
+        if let Some(replay) = &mut self.replay {
+            observers.push(replay);
+        }
+
+        observers
+    }
+
+    /// Continue the hall of fame of a checkpoint, its genomes are kept
+    /// if this run records one
+
+    pub fn resume(&mut self, saved: Option<HallOfFame>) {
+        if let (Some(hall_of_fame), Some(saved)) = (&mut self.hall_of_fame, saved) {
+            for genome in saved.genomes {
+                hall_of_fame.insert(genome);
+            }
+        }
+    }
+
+    /// Save the checkpoint if it's due, the Poblation must
+    /// be between two generations
+
+    pub fn checkpoint(&self, poblation: &Poblation) -> io::Result<()> {
+
+        let finished = poblation.actual_gen - 1;
+
+        match &self.checkpoint {
+            Some(path) if finished.is_multiple_of(self.checkpoint_every) => {
+                let mut checkpoint = Checkpoint::capture(poblation);
+                checkpoint.hall_of_fame = self.hall_of_fame.clone();
+                checkpoint.save(path)
+            },
+            _ => Ok(()),
+        }
+    }
+
+    /// Flush the event log and write the files of the finished run
+
+    pub fn finish(self, poblation: &Poblation, converged: bool) -> io::Result<()> {
+
+        let wall_clock = self.clock.elapsed().as_secs_f64();
+
+        if let Some(events) = self.events {
+            events.finish()?;
+        }
+
+        let started_at = self.started_at.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        let metadata = RunMetadata::new(&poblation.config, started_at, wall_clock, converged);
+
+        if let Some(path) = &self.stats_out {
+            export::write_stats(path, &metadata, &poblation.generations)?;
+        }
+
+        if let (Some(path), Some(hall_of_fame)) = (&self.hall_of_fame_out, &self.hall_of_fame) {
+            hall_of_fame.save(path)?;
+        }
+
+        let replay = self.replay.map(ReplayRecorder::finish);
+
+        if let (Some(path), Some(replay)) = (&self.replay_out, &replay) {
+            replay.save(path)?;
+        }
+
+        if let Some(path) = &self.report {
+
+            let frame = replay.as_ref()
+                .and_then(|r| r.generations.last())
+                .and_then(|g| g.frames(&poblation.board).pop());
+
+            let report = Report {
+                metadata: &metadata,
+                generations: &poblation.generations,
+                genomes: self.hall_of_fame.as_ref().map(|h| h.genomes.as_slice()).unwrap_or_default(),
+                frame: frame.as_ref(),
+            };
+
+            report.write(path)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Create an output file, the error has its path
+
+fn create(path: &Path) -> io::Result<File> {
+    File::create(path).map_err(|e| path_error(path, e))
+}
+
+fn path_error(path: &Path, e: io::Error) -> io::Error {
+    io::Error::new(e.kind(), format!("{}: {}", path.display(), e))
+}
@@ -1,16 +1,21 @@
 
-use std::collections::HashMap;
 use std::time::Duration;
 use std::vec;
 
 use rand::Rng;
-use colored::*;
 use textplots::{Chart, Plot, Shape};
 
 use crate::position::*;
-use crate::constants::*;
-use crate::random::random;
-use crate::entity::Entity;
+use crate::board::Board;
+use crate::topology::Topology;
+use crate::occupancy::Occupancy;
+use crate::frame::Frame;
+use crate::genome::Genome;
+use crate::entity::{Entity, Movement};
+use crate::random::{self, SimRng};
+use crate::stats::GenerationStats;
+use crate::config::SimulationConfig;
+use crate::observer::SimulationObserver;
 use crate::utils::{cumulative, normalize, trunc_uuid};
 
 /// Poblation struct
@@ -18,77 +23,196 @@ use crate::utils::{cumulative, normalize, trunc_uuid};
 /// # Attributes
 /// 
 /// * `entities` - Poblation entities
+/// * `on_goal_entities` - Entities that reached the goal in the current generation
+/// * `murders` - Murders of the current generation
+/// * `blocked_moves` - Moves of the current generation that would have left the board
+/// * `falls` - Entities of the current generation that fell off a lethal edge
+/// * `steps_to_goal` - Iteration at which each goal entity arrived
+/// * `generations` - Statistics of every finished generation
+/// * `iteration` - Iterations run in the current generation
+/// * `converged` - Whether a generation converged, the simulation doesn't advance after it
+/// * `config` - Simulation configuration
+/// * `board` - Board built from the configuration
+/// * `occupancy` - Cells of the alive entities, rebuilt from `entities` at the
+///   start of every iteration so callers can edit them between steps
+/// * `rng` - Random generator owned by the simulation
 /// 
 /// # Methods
 /// 
 /// * `new` - Create a new Poblation
 /// * `run` - Run the simulation of the Poblation
+/// * `step_generation` - Run one generation
+/// * `step_iteration` - Run one movement iteration
+/// * `*_with` - The same methods notifying a SimulationObserver
 /// * `show` - Show the Poblation state
 
 #[derive(Clone, Debug)]
 pub struct Poblation {
     pub entities: Vec<Entity>,
     pub history: Vec<Vec<Entity>>,
-    pub stadistics: HashMap<&'static str, Vec<(f32, f32)>>,
-    pub actual_gen: usize
+    pub generations: Vec<GenerationStats>,
+    pub actual_gen: usize,
+    pub on_goal_entities: Vec<Entity>,
+    pub murders: usize,
+    pub blocked_moves: usize,
+    pub falls: usize,
+    pub steps_to_goal: Vec<usize>,
+    pub iteration: usize,
+    pub converged: bool,
+    pub config: SimulationConfig,
+    pub board: Board,
+    occupancy: Occupancy,
+    pub rng: SimRng,
 }
 
 impl Poblation {
 
-    pub fn new() -> Self {
+    /// Create a new Poblation
+    /// 
+    /// # Arguments
+    /// 
+    /// * `config` - Simulation configuration, it must be valid
+    ///   (`SimulationConfig::validate`)
+    /// 
+    /// The random generator is seeded with `config.seed`, if there's
+    /// no seed a new one is drawn and recorded in the config so the
+    /// run can be reproduced
+
+    pub fn new(mut config: SimulationConfig) -> Self {
+        let seed = *config.seed.get_or_insert_with(random::entropy_seed);
+        Poblation::with_rng(config, random::generator(seed))
+    }
+
+    /// Create a new Poblation with the given random generator
+    /// 
+    /// # Arguments
+    /// 
+    /// * `config` - Simulation configuration, it must be valid
+    /// * `rng` - Random generator used by every step of the simulation
+
+    pub fn with_rng(config: SimulationConfig, rng: SimRng) -> Self {
+
+        // Los genomas de la configuración reemplazan a las entidades aleatorias
+
+        if !config.genomes.is_empty() {
+            let genomes = config.genomes.clone();
+            return Poblation::with_genomes(config, &genomes, rng)
+        }
+
+        Poblation::place(config, rng, Entity::new)
+    }
+
+    /// Create a new Poblation from saved genomes instead of random entities
+    /// 
+    /// # Arguments
+    /// 
+    /// * `config` - Simulation configuration, it must be valid
+    /// * `genomes` - Genomes of the first generation, they're repeated
+    ///   in order if there are fewer than `config.sample`. They're
+    ///   recorded in `config.genomes` so the run can be reproduced
+    /// * `rng` - Random generator used by every step of the simulation
+
+    pub fn with_genomes(mut config: SimulationConfig, genomes: &[Genome], rng: SimRng) -> Self {
 
+        assert!(!genomes.is_empty(), "at least one genome is needed");
+
+        config.genomes = genomes.to_vec();
+
+        let mut genomes = genomes.iter().cycle();
+
+        Poblation::place(config, rng, |position, config, rng| {
+            genomes.next().unwrap().entity(position, config, rng)
+        })
+    }
+
+    /// Place `config.sample` entities on random free start cells
+
+    fn place<F>(config: SimulationConfig, mut rng: SimRng, mut create: F) -> Self
+    where
+        F: FnMut(Position, &SimulationConfig, &mut SimRng) -> Entity,
+    {
+
+        let board = Board::new(&config);
+        let mut occupancy = Occupancy::new();
         let mut entities: Vec<Entity> = Vec::new();
 
-        let mut i = SAMPLE;
+        let mut i = config.sample;
 
         while i > 0 {
 
-            let random_row = random().gen_range(0..DIMENSIONS.0) as isize;
-            let random_col = random().gen_range(0..=1) as isize;
-
-            let new_pos = Point::new(random_col, random_row);
+            let new_pos = board.random_start(&mut rng);
 
-            if entities.iter().any(|e| e.get_position() == new_pos) {
+            if occupancy.is_occupied(new_pos) {
                 continue;
             }
 
-            entities.push(Entity::new(Position::Some(new_pos)));
+            occupancy.insert(new_pos, entities.len());
+            entities.push(create(Position::Some(new_pos), &config, &mut rng));
 
             i -= 1;
         }
 
-        let mut stadistics: HashMap<&'static str, Vec<(f32, f32)>> = HashMap::new();
-        stadistics.insert("murders", Vec::new());
-        stadistics.insert("winners", Vec::new());
-        
-        Poblation { entities, history: Vec::new(), stadistics , actual_gen: 1}
+        Poblation::from_entities(config, entities, rng)
+    }
+
+    /// Create a Poblation on its first generation from already placed entities
+    /// 
+    /// # Arguments
+    /// 
+    /// * `config` - Simulation configuration, it must be valid
+    /// * `entities` - Entities of the first generation, with a position
+    /// * `rng` - Random generator used by every step of the simulation
+
+    pub fn from_entities(config: SimulationConfig, entities: Vec<Entity>, rng: SimRng) -> Self {
+
+        let board = Board::new(&config);
+
+        Poblation {
+            occupancy: Occupancy::from_entities(&entities),
+            board,
+            entities,
+            history: Vec::new(),
+            generations: Vec::new(),
+            actual_gen: 1,
+            on_goal_entities: Vec::new(),
+            murders: 0,
+            blocked_moves: 0,
+            falls: 0,
+            steps_to_goal: Vec::new(),
+            iteration: 0,
+            converged: false,
+            config,
+            rng,
+        }
     }
 
-    pub fn assign_positions(&self, entities: &mut Vec<Entity>) {
+    pub fn assign_positions(&mut self, entities: &mut [Entity]) {
+
+        // Celdas ya asignadas a las nuevas entidades
+
+        let mut occupancy = Occupancy::from_entities(entities);
 
         let mut i = 0;
 
         while i < entities.len() {
 
-            let random_row = random().gen_range(0..DIMENSIONS.0) as isize;
-            let random_col = random().gen_range(0..=1) as isize;
-            
-            let new_pos = Point::new(random_col, random_row);
+            let new_pos = self.board.random_start(&mut self.rng);
 
             // Si la posición ya está ocupada por otra entidad, continue
-            // .any() => Devuelve el booleano correspondiente a la condición
 
-            if entities.iter().any(|e| e.position.is_some() && e.get_position() == new_pos) {
+            if occupancy.is_occupied(new_pos) {
                 continue
             }
 
+            occupancy.insert(new_pos, i);
             entities[i].position = Position::Some(new_pos);
+            entities[i].fitness = self.config.n_iterations;
 
             i += 1;
         }
     }
 
-    pub fn selection(&self, final_entities: Vec<Entity>) -> Vec<Entity> {
+    pub fn selection(&mut self, final_entities: Vec<Entity>, observer: &mut dyn SimulationObserver) -> Vec<Entity> {
 
         // Se crea un vector de nuevas entidades vacio
         
@@ -102,16 +226,21 @@ impl Poblation {
         // no representará un mayor cambio en el fitness total de la población ya que la probabilidad
         // de que sean seleccionadas como padres será muy baja
 
-        while entities.len() != DIMENSIONS.0 {
-            entities.push(Entity::new(Position::None));
+        // Se necesitan al menos dos padres distintos
+
+        let parents = self.board.goal_capacity().max(entities.len()).max(2);
+        let p = self.config.p;
+
+        while entities.len() < parents {
+            entities.push(Entity::new(Position::None, &self.config, &mut self.rng));
         }
 
         // Se calculan las probabilidades de selección de las entidades
 
-        let mut probs: Vec<f64> = vec![P];
+        let mut probs: Vec<f64> = vec![p];
 
-        for i in 1..DIMENSIONS.0 {
-            probs.push(P*(1 as f64 - P).powi(i as i32));
+        for i in 1..parents {
+            probs.push(p * (1.0 - p).powi(i as i32));
         }
 
         // Se normalizan las probabilidades
@@ -122,15 +251,15 @@ impl Poblation {
 
         // Se inicia el proceso de creación de las nuevas entidades
 
-        while new_entities.len() != SAMPLE {
+        while new_entities.len() != self.config.sample {
 
             // Obtenemos 2 probabilidades aleatorias, no pueden ser iguales
             
-            let prob_1 = random().gen::<f64>();
-            let mut prob_2 = random().gen::<f64>();
+            let prob_1 = self.rng.gen::<f64>();
+            let mut prob_2 = self.rng.gen::<f64>();
 
             while prob_1 == prob_2 {
-                prob_2 = random().gen::<f64>();
+                prob_2 = self.rng.gen::<f64>();
             }
 
             // Obtener los indices de las entidades que se cruzarán en base a las probabilidades
@@ -142,18 +271,29 @@ impl Poblation {
             let mut c2_index = cumulative.iter().position(|&p| p > prob_2).unwrap();
 
             while c1_index == c2_index {
-                prob_2 = random().gen::<f64>();
+                prob_2 = self.rng.gen::<f64>();
                 c2_index = cumulative.iter().position(|&p| p > prob_2).unwrap();
             }
 
             // Se obtienen las entidades que se cruzarán y se les aplican los operadores de cruce
 
-            let childrens = entities[c1_index].clone() + entities[c2_index].clone();
+            let parents = (&entities[c1_index], &entities[c2_index]);
+            let (mut children_1, mut children_2) = parents.0.crossover(parents.1, &mut self.rng);
+
+            observer.on_birth(parents, &children_1);
+            observer.on_birth(parents, &children_2);
+
+            for children in [&mut children_1, &mut children_2] {
+
+                if let Some(mutation) = children.mutate(&self.config, &mut self.rng) {
+                    observer.on_mutation(children, mutation);
+                }
+            }
 
             // Se añaden las nuevas entidades al vector de nuevas entidades
 
-            new_entities.push(childrens.0);
-            new_entities.push(childrens.1);
+            new_entities.push(children_1);
+            new_entities.push(children_2);
         }
 
         // Finalmente se asignan las posiciones a las nuevas entidades 
@@ -161,198 +301,349 @@ impl Poblation {
 
         self.assign_positions(&mut new_entities);
 
+        observer.on_selection(&entities, &new_entities);
+
         new_entities
     }
 
-    pub fn run(&mut self) {
-
-        let mut generation = 1;
-
-        let mut murders = 0;
-
-        while generation <= N_GENERATIONS {
-
-            let mut on_goal_entities: Vec<Entity> = Vec::new();
-
-            // Iteramos en la cantidad de iteraciones (movimientos por entidad)
-    
-            for iteration in 1..=N_ITERATIONS {
-
-                // Vector de entidades muertas en la iteración
-
-                let mut dead_entities: Vec<usize> = Vec::new();
-
-                // Iteramos en las entidades de la población actual
-    
-                for i in 0..self.entities.len() {
-    
-                    if !self.entities[i].alive { continue }
-
-                    // Si la entidad actual está en el vector de la meta, continue
-
-                    // .any() => Devuelve el booleano correspondiente a la condición
-    
-                    if on_goal_entities.iter().any(|e| e.id == self.entities[i].id) { 
-                        continue 
-                    }
-
-                    // Calculamos la siguiente posición de la entidad actual
-    
-                    let entity_next_pos: Point = self.entities[i].next_position();
-
-                    // Buscamos si hay otra entidad en la siguiente posición de la entidad actual
-
-                    // .position() => Devuelve el indice que cumple con la condición
-    
-                    let next_pos_index: Option<usize> = self.entities.iter().position(
-                        |e| e.get_position() == entity_next_pos && e.alive && e.id != self.entities[i].id
-                    );
-
-                    // next_pos_index es un indice opcional, si es Some, entonces hay una entidad en 
-                    // la siguiente posición, por lo tanto deberemos comprobar las condiciones de asesinato
-
-                    if let Some(j) = next_pos_index {
-    
-                        if self.entities[i].is_killer() && !self.entities[j].is_killer() {
-                            dead_entities.push(j);
-                            self.entities[j].position = Position::Some(entity_next_pos);
-                            murders += 1;
-    
-                        } else if !self.entities[i].is_killer() && self.entities[j].is_killer() {
-                            dead_entities.push(i);
-                            self.entities[j].position = Position::Some(entity_next_pos);
-                            murders += 1;
-    
-                        } else if self.entities[i].is_killer() && self.entities[j].is_killer() {
-                            dead_entities.push(i); dead_entities.push(j);
-                            murders += 2;
-                        }
-                    
-                    } else {
-                        self.entities[i].position = Some(entity_next_pos);
-                    }
-
-                    // Por otro lado si no hay entidad en la siguiente posición, entonces la entidad
-                    // actual se mueve a la siguiente posición
-
-                    // Se recorren las entidades muertas y se les asigna el estado de no viva
-                    // index (&usize) corresponde al indice de la entidad en el vector de entidades
-                    
-                    for index in dead_entities.iter() {
-                        self.entities[*index].alive = false // *index => Valor de la referencia
-                    }
-
-                    // Si la entidad actual está en la meta, la agregamos al vector de la meta
-
-                    if self.entities[i].get_position().x == DIMENSIONS.1 as isize - 1 {
-                        on_goal_entities.push(self.entities[i].clone());
-                        continue
-                    }
-                }
-                
-                self.history.push(self.entities.clone());
+    /// Run the simulation of the Poblation until a generation
+    /// converges or `n_generations` is reached
+    /// 
+    /// # Returns
+    /// 
+    /// * `bool` - Whether a generation converged before `n_generations`
 
-                // Mostrar la población cada SHOW_THRESHOLD generaciones
+    pub fn run(&mut self) -> bool {
+        self.run_with(&mut ())
+    }
 
-                if generation % SHOW_THRESHOLD == 0 {
-                    self.show(generation, iteration, None)
-                }
+    /// Run the simulation notifying the observer of every event
+
+    pub fn run_with(&mut self, observer: &mut dyn SimulationObserver) -> bool {
+
+        while self.actual_gen <= self.config.n_generations {
+
+            if self.step_generation_with(observer) {
+                return true
             }
+        }
+
+        false
+    }
+
+    /// Run the remaining iterations of the current generation
+    /// (`actual_gen`) and finish it with `finish_generation`
+    /// 
+    /// # Returns
+    /// 
+    /// * `bool` - Whether the generation converged, in that case
+    ///   the entities and the history of the generation are kept
+
+    pub fn step_generation(&mut self) -> bool {
+        self.step_generation_with(&mut ())
+    }
+
+    pub fn step_generation_with(&mut self, observer: &mut dyn SimulationObserver) -> bool {
+
+        while !self.step_iteration_with(observer) {}
+
+        self.finish_generation_with(observer)
+    }
+
+    /// Move every alive entity once (one iteration of the current generation)
+    /// 
+    /// The state can be inspected between calls: `entities` positions and
+    /// alive flags, `on_goal_entities`, `murders`, `blocked_moves`, `falls` and `iteration`
+    /// 
+    /// # Returns
+    /// 
+    /// * `bool` - Whether every iteration of the generation was run,
+    ///   after that `finish_generation` must be called
+
+    pub fn step_iteration(&mut self) -> bool {
+        self.step_iteration_with(&mut ())
+    }
+
+    pub fn step_iteration_with(&mut self, observer: &mut dyn SimulationObserver) -> bool {
+
+        if self.converged || self.iteration == self.config.n_iterations {
+            return true
+        }
+
+        if self.iteration == 0 {
+            observer.on_generation_start(self.actual_gen, &self.entities);
+        }
+
+        self.iteration += 1;
+
+        let generation = self.actual_gen;
+        let iteration = self.iteration;
+
+        observer.on_iteration_start(generation, iteration);
 
-            let x = (generation - 1) as f32;
+        // Las entidades son públicas, el índice se reconstruye por si cambiaron entre pasos
 
-            self.stadistics.get_mut("murders").unwrap().push((x, murders as f32 / SAMPLE as f32));
-            self.stadistics.get_mut("winners").unwrap().push((x, on_goal_entities.len() as f32 / SAMPLE as f32));
+        self.reindex();
 
-            // Ordenar las entidades finales por su fitness (menor a mayor)
+        // Vector de entidades muertas en la iteración
 
-            on_goal_entities.sort_by(|a, b| a.fitness.cmp(&b.fitness));
+        let mut dead_entities: Vec<usize> = Vec::new();
 
-            // Si la cantidad de entidades en la meta es igual a la cantidad de entidades,
-            // hay una convergencia, por lo tanto se muestra el resultado y se termina la simulación
+        // Iteramos en las entidades de la población actual
 
-            if on_goal_entities.len() == DIMENSIONS.0 {
+        for i in 0..self.entities.len() {
 
-                for i in 0..self.history.len() {
-                    self.show(generation, i + 1, Some(&self.history[i]));
+            if !self.entities[i].alive { continue }
+
+            // Si la entidad actual está en el vector de la meta, continue
+
+            // Solo se busca en el vector si la entidad está en una celda de meta
+            // .any() => Devuelve el booleano correspondiente a la condición
+
+            if self.board.is_goal(self.entities[i].get_position()) && self.on_goal_entities.iter().any(|e| e.id == self.entities[i].id) { 
+                continue 
+            }
+
+            // Calculamos la siguiente posición de la entidad actual
+
+            let entity_pos = self.entities[i].get_position();
+
+            // Si el movimiento sale del tablero o choca con un muro la entidad se queda en su posición
+
+            let entity_next_pos: Point = match self.entities[i].next_position(&self.board, &mut self.rng) {
+                Movement::To(point) => point,
+                Movement::Blocked => {
+                    self.blocked_moves += 1;
+                    continue
+                },
+                Movement::Fall => {
+                    self.entities[i].alive = false;
+                    self.occupancy.remove(self.entities[i].get_position());
+                    self.falls += 1;
+                    observer.on_fall(&self.entities[i]);
+                    continue
+                },
+            };
+
+            // Buscamos si hay otra entidad en la siguiente posición de la entidad actual,
+            // el índice de ocupación solo tiene entidades vivas
+
+            let next_pos_index: Option<usize> = self.occupancy.get(entity_next_pos).filter(|&j| j != i);
+
+            // next_pos_index es un indice opcional, si es Some, entonces hay una entidad en 
+            // la siguiente posición, por lo tanto deberemos comprobar las condiciones de asesinato
+
+            if let Some(j) = next_pos_index {
+
+                if self.entities[i].is_killer() && !self.entities[j].is_killer() {
+                    dead_entities.push(j);
+                    self.entities[j].position = Position::Some(entity_next_pos);
+                    self.occupancy.remove(entity_next_pos);
+                    self.murders += 1;
+                    observer.on_kill(&self.entities[i], &self.entities[j]);
+
+                } else if !self.entities[i].is_killer() && self.entities[j].is_killer() {
+                    dead_entities.push(i);
+                    self.entities[j].position = Position::Some(entity_next_pos);
+                    self.occupancy.remove(entity_pos);
+                    self.murders += 1;
+                    observer.on_kill(&self.entities[j], &self.entities[i]);
+
+                } else if self.entities[i].is_killer() && self.entities[j].is_killer() {
+                    dead_entities.push(i); dead_entities.push(j);
+                    self.occupancy.remove(entity_pos);
+                    self.occupancy.remove(entity_next_pos);
+                    self.murders += 2;
+                    observer.on_mutual_kill(&self.entities[i], &self.entities[j]);
                 }
+            
+            } else {
+                self.entities[i].position = Some(entity_next_pos);
 
-                break
+                if entity_next_pos != entity_pos {
+                    self.occupancy.relocate(entity_pos, entity_next_pos);
+                    observer.on_move(&self.entities[i], entity_pos, entity_next_pos);
+                }
             }
 
-            // De lo contrario, se realiza la selección de las entidades finales
+            // Por otro lado si no hay entidad en la siguiente posición, entonces la entidad
+            // actual se mueve a la siguiente posición
+
+            // Se recorren las entidades muertas y se les asigna el estado de no viva
+            // index (&usize) corresponde al indice de la entidad en el vector de entidades
+            
+            for index in dead_entities.iter() {
+                self.entities[*index].alive = false // *index => Valor de la referencia
+            }
 
-            self.history.clear();
-            self.entities = self.selection(on_goal_entities);
+            // Si la entidad actual está en la meta, su fitness se multiplica por
+            // la recompensa de esa meta y la agregamos al vector de la meta
+
+            if let Some(weight) = self.board.goal_weight(self.entities[i].get_position()) {
+                self.entities[i].fitness = (self.entities[i].fitness as f64 * weight).round() as usize;
+                self.on_goal_entities.push(self.entities[i].clone());
+                self.steps_to_goal.push(iteration);
+                observer.on_goal_reached(&self.entities[i]);
+            }
+        }
+        
+        self.history.push(self.entities.clone());
 
-            // Y se avanza a la siguiente generación
+        // Mostrar la población cada show_threshold generaciones
 
-            murders = 0;
-            generation += 1;
-            self.actual_gen += 1;
+        if !self.config.headless && generation.is_multiple_of(self.config.show_threshold) {
+            self.show(generation, iteration, None)
         }
 
-        return
+        self.iteration == self.config.n_iterations
     }
 
-    pub fn show(&self, n_generation: usize, n_iteration: usize, history: Option<&Vec<Entity>>) {
+    /// Record the statistics of the current generation and, if it
+    /// didn't converge, select the entities of the next one. After a
+    /// generation converged it does nothing
+    /// 
+    /// # Returns
+    /// 
+    /// * `bool` - Whether the generation converged
 
-        let entities = match history {
-            Some(h) => h,
-            None => &self.entities
-        };
+    pub fn finish_generation(&mut self) -> bool {
+        self.finish_generation_with(&mut ())
+    }
 
-        let mut buffer = String::new();
-        let total_width = DIMENSIONS.1 * 7;
+    pub fn finish_generation_with(&mut self, observer: &mut dyn SimulationObserver) -> bool {
 
-        buffer.push_str(&format!("\x1B[2J\x1B[1;1H"));
-        buffer.push_str(&format!("+{:-<1$}+\n", "", total_width));
+        // Una generación que ya convergió no se vuelve a registrar
 
-        let header = format!("| Generation: {:<5} Movement: {:<5} {}|", n_generation, n_iteration, " ".repeat(total_width - 36));
-        buffer.push_str(&format!("{}\n", header));
-        buffer.push_str(&format!("+{:-<1$}+\n", "", total_width));
+        if self.converged {
+            return true
+        }
 
-        for y in 0..DIMENSIONS.0 {
+        let generation = self.actual_gen;
 
-            for _ in 0..3 {
+        // Si todas las celdas de la meta (o todas las entidades) llegaron hay una
+        // convergencia, por lo tanto se muestra el resultado y se termina la simulación
 
-                buffer.push_str("|");
+        let converged = self.on_goal_entities.len() >= self.goal_target();
 
-                for x in 0..DIMENSIONS.1 {
+        let stats = GenerationStats::compute(self, converged);
 
-                    let current_pos = Point::new(x as isize, y as isize);
+        observer.on_generation_end(&stats);
+        self.generations.push(stats);
 
-                    if let Some(e) = entities.iter().find(|e| e.get_position() == current_pos && e.alive) {
+        // Ordenar las entidades finales por el peso de su meta (mayor a menor), así las
+        // metas con más recompensa ponen a sus entidades primero en la selección, y
+        // las de una misma meta por su fitness (menor a mayor)
 
-                        if e.is_killer() {
-                            buffer.push_str(&format!("{}", (0..2).map(|_| "*".custom_color(e.color).to_string()).collect::<String>()));
-                            buffer.push_str(&format!("{}", (0..2).map(|_| "*".white().to_string()).collect::<String>()));
-                            buffer.push_str(&format!("{}|", (0..2).map(|_| "*".custom_color(e.color).to_string()).collect::<String>()));
-                        } else {
-                            buffer.push_str(&format!("{}|", (0..6).map(|_| "*".custom_color(e.color).to_string()).collect::<String>()));
-                        }
+        let weight = |e: &Entity| self.board.goal_weight(e.get_position()).unwrap_or(1.0);
 
-                    } else {
-                        buffer.push_str(&format!(" {}|", " ".repeat(5)));
-                    }
-                }
+        self.on_goal_entities.sort_by(|a, b| weight(b).total_cmp(&weight(a)).then(a.fitness.cmp(&b.fitness)));
 
-                buffer.push_str("\n");
-            }
+        if converged {
+
+            self.converged = true;
 
-            if y < DIMENSIONS.0 - 1 {
-                buffer.push_str(&format!("+{:-<1$}+\n", "", total_width));
+            for i in 0..self.history.len() {
+
+                if self.config.headless { break }
+
+                self.show(generation, i + 1, Some(&self.history[i]));
             }
+
+            return true
         }
 
-        buffer.push_str(&format!("+{:-<1$}+\n", "", total_width));
+        // De lo contrario, se realiza la selección de las entidades finales
+
+        let on_goal_entities = std::mem::take(&mut self.on_goal_entities);
+
+        self.history.clear();
+        self.entities = self.selection(on_goal_entities, observer);
+        self.reindex();
+
+        // Y se avanza a la siguiente generación
+
+        self.murders = 0;
+        self.blocked_moves = 0;
+        self.falls = 0;
+        self.steps_to_goal.clear();
+        self.iteration = 0;
+        self.actual_gen += 1;
+
+        false
+    }
+
+    /// Rebuild the index of the cells of the alive entities. Every
+    /// iteration rebuilds it, callers only need it to use `entity_at`
+    /// right after editing `entities`
+
+    pub fn reindex(&mut self) {
+        self.occupancy = Occupancy::from_entities(&self.entities);
+    }
+
+    /// Get the alive entity on a cell
+    ///
+    /// # Returns
+    ///
+    /// * `Option<&Entity>` - The entity, None if the cell is empty or
+    ///   the index doesn't match `entities` (they were edited without `reindex`)
+
+    pub fn entity_at(&self, point: Point) -> Option<&Entity> {
+        self.occupancy.get(point)
+            .and_then(|index| self.entities.get(index))
+            .filter(|e| e.alive && e.position == Some(point))
+    }
+
+    /// Entities that must reach the goal for a generation to converge,
+    /// one per goal cell unless there are more cells than entities
+
+    pub fn goal_target(&self) -> usize {
+        self.board.goal_capacity().min(self.config.sample)
+    }
+
+    pub fn show(&self, n_generation: usize, n_iteration: usize, history: Option<&Vec<Entity>>) {
+
+        let entities = match history {
+            Some(h) => h,
+            None => &self.entities
+        };
+
+        let frame = Frame::new(n_generation, n_iteration, &self.board, entities);
+
+        print!("{}", frame.render());
+
+        std::thread::sleep(Duration::from_millis(self.config.frame_delay));
+    }
+
+    /// Get a statistics series as (generation - 1, value) pairs,
+    /// generations without a value for the series are skipped
+    /// 
+    /// # Arguments
+    /// 
+    /// * `key` - Series name (`GenerationStats::value`)
+    /// 
+    /// # Returns
+    /// 
+    /// * `Option<Vec<(f32, f32)>>` - None if the key isn't a series
+
+    pub fn statistics(&self, key: &str) -> Option<Vec<(f32, f32)>> {
+
+        if !GenerationStats::is_series(key) {
+            return None
+        }
 
-        print!("{}", buffer);
+        let series = self.generations.iter()
+            .filter_map(|stats| stats.value(key).map(|v| ((stats.generation - 1) as f32, v as f32)))
+            .collect();
 
-        std::thread::sleep(Duration::from_millis(25));
+        Some(series)
     }
 
-    pub fn graphic(&self, key: &'static str) {
+    pub fn graphic(&self, key: &str) {
+
+        let Some(series) = self.statistics(key) else {
+            println!("Unknown statistics series: {}", key);
+            return
+        };
 
         let total_width = 80;
 
@@ -361,101 +652,116 @@ impl Poblation {
         println!("\n{}\n", header);
         
         Chart::new(220, 80, 0.0, self.actual_gen as f32)
-            .lineplot(&Shape::Lines(&self.stadistics.get(key).unwrap()))
+            .lineplot(&Shape::Lines(&series))
             .display();
     }
 }
 
 impl Poblation {
 
+    /// Run the first generation printing every movement and murder
+
     pub fn run_debug(&mut self) {
+        self.run_debug_with(&mut ())
+    }
+
+    pub fn run_debug_with(&mut self, observer: &mut dyn SimulationObserver) {
 
         let killers = self.entities.iter()
             .filter(|e| e.is_killer()).map(|e| trunc_uuid(&e.id))
             .collect::<Vec<String>>()
         ;
 
-        let mut moves = Vec::new();
+        let mut log = DebugLog::default();
         
         println!("\nInitial state");
         println!("Killers: {:?}", killers);
 
-        for iteration in 1..=N_ITERATIONS {
-            
-            let mut dead_entities = Vec::new();
-
-            for i in 0..self.entities.len() {
-
-                if !self.entities[i].alive { continue }
-                
-                let entity_next_pos = self.entities[i].next_position();
-                println!("E{}: {:?}", trunc_uuid(&self.entities[i].id), entity_next_pos);
-
-                let next_pos = self.entities.iter().position(
-                    |e| e.get_position() == entity_next_pos && e.alive && e.id != self.entities[i].id
-                );
-
-                if let Some(j) = next_pos {
+        for iteration in 1..=self.config.n_iterations {
 
-                    if self.entities[i].is_killer() && !self.entities[j].is_killer() {
-                        dead_entities.push(j);
-                        self.entities[i].position = Some(entity_next_pos);
-                        moves.push(format!("E{} killed E{}", trunc_uuid(&self.entities[i].id), trunc_uuid(&self.entities[j].id)));
-
-                    } else if !self.entities[i].is_killer() && self.entities[j].is_killer() {
-                        dead_entities.push(i);
-                        self.entities[j].position = Some(entity_next_pos);
-                        moves.push(format!("E{} killed E{}", trunc_uuid(&self.entities[j].id), trunc_uuid(&self.entities[i].id)));
-
-                    } else if self.entities[i].is_killer() && self.entities[j].is_killer() {
-                        dead_entities.push(i); dead_entities.push(j);
-                        moves.push(format!("E{} and E{} killed each other", trunc_uuid(&self.entities[i].id), trunc_uuid(&self.entities[j].id)));
-                    }
-                
-                } else {
-                    self.entities[i].position = Some(entity_next_pos);
-                }
-                
-                for &i in dead_entities.iter() {
-                    self.entities[i].alive = false;
-                }
-            }
+            self.step_iteration_with(&mut vec![&mut log as &mut dyn SimulationObserver, &mut *observer]);
 
             println!("Iteration {}", iteration);
             self.show_debug();
-            println!("Moves: {:?}\n", moves);
+            println!("Moves: {:?}\n", log.moves);
 
-            moves.clear();
+            log.moves.clear();
         }
     }
 
     pub fn show_debug(&self) {
+
+        let (rows, cols) = self.board.dimensions;
+
+        // En un tablero hexagonal las filas impares se desplazan media celda
+
+        let hex = self.board.topology == Topology::Hex;
+        let width = if hex { cols * 12 + 6 } else { cols * 12 };
+
         println!();
-        println!("+{:-<12}+", "-".repeat(DIMENSIONS.1 * 12));
+        println!("+{:-<12}+", "-".repeat(width));
 
-        for y in 0..DIMENSIONS.0 {
+        for y in 0..rows {
             print!("|");
 
-            for x in 0..DIMENSIONS.1 {
+            if self.board.is_shifted(y) {
+                print!("{}", " ".repeat(6));
+            }
+
+            for x in 0..cols {
                 let current_post = Point::new(x as isize, y as isize);
 
-                if let Some(entity) = self.entities.iter().find(|e| e.get_position() == current_post && e.alive) {
+                if let Some(entity) = self.entity_at(current_post) {
                     print!(" {:^10}|", format!("E{}", trunc_uuid(&entity.id)));
 
+                } else if self.board.is_wall(current_post) {
+                    print!(" {:^10}|", "#".repeat(10));
+
                 } else {
                     print!(" {:^10}|", " ");
                 }
             }
 
+            if hex && !self.board.is_shifted(y) {
+                print!("{}|", " ".repeat(5));
+            }
+
             println!();
-            if y < DIMENSIONS.0 - 1 {
-                println!("+{:-<12}+", "-".repeat(DIMENSIONS.1 * 12));
+            if y < rows - 1 {
+                println!("+{:-<12}+", "-".repeat(width));
             }
         }
 
-        println!("+{:-<11}+", "-".repeat(DIMENSIONS.1 * 12));
+        println!("+{:-<11}+", "-".repeat(width));
         println!();
     }
 
 
 }
+
+/// Observer used by `run_debug`, prints every movement
+/// and keeps the murders of the current iteration
+
+#[derive(Default)]
+struct DebugLog {
+    moves: Vec<String>,
+}
+
+impl SimulationObserver for DebugLog {
+
+    fn on_move(&mut self, entity: &Entity, _from: Point, to: Point) {
+        println!("E{}: {:?}", trunc_uuid(&entity.id), to);
+    }
+
+    fn on_kill(&mut self, killer: &Entity, victim: &Entity) {
+        self.moves.push(format!("E{} killed E{}", trunc_uuid(&killer.id), trunc_uuid(&victim.id)));
+    }
+
+    fn on_mutual_kill(&mut self, first: &Entity, second: &Entity) {
+        self.moves.push(format!("E{} and E{} killed each other", trunc_uuid(&first.id), trunc_uuid(&second.id)));
+    }
+
+    fn on_fall(&mut self, entity: &Entity) {
+        self.moves.push(format!("E{} fell off the board", trunc_uuid(&entity.id)));
+    }
+}
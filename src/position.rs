@@ -1,5 +1,6 @@
 
 use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, ops::Add};
 
 /// Position type
@@ -14,7 +15,7 @@ pub type Position = Option<Point>;
 /// 
 /// Represents a point (x, y)
 
-#[derive(Debug, Clone, Copy, PartialEq, Hash, Eq, )]
+#[derive(Debug, Clone, Copy, PartialEq, Hash, Eq, Serialize, Deserialize)]
 pub struct Point {
     pub x: isize,
     pub y: isize
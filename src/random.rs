@@ -1,41 +1,83 @@
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha12Rng;
 
-use std::env::args;
-use lazy_static::lazy_static;
-use rand::{rngs::StdRng, SeedableRng};
-use std::sync::{Arc, Mutex, MutexGuard};
+/// Random generator owned by each simulation
+/// 
+/// It's the generator behind `rand::rngs::StdRng`, used directly
+/// because its state can be serialized in checkpoints
 
-use crate::constants::SEED;
+pub type SimRng = ChaCha12Rng;
 
-pub struct RngGenerator;
-pub type RandomGenerator = Arc<Mutex<StdRng>>;
+/// Create a simulation generator, the same seed
+/// always produces the same simulation
+/// 
+/// # Arguments
+/// 
+/// * `seed` - Seed of the generator
 
-impl RngGenerator {
+pub fn generator(seed: u64) -> SimRng {
+    SimRng::seed_from_u64(seed)
+}
 
-    pub fn new(rng: StdRng) -> RandomGenerator {
-        Arc::new(Mutex::new(rng))
-    }
+/// Draw a new seed from the operating system entropy,
+/// used when a run doesn't specify one
+
+pub fn entropy_seed() -> u64 {
+    rand::rngs::OsRng.next_u64()
 }
 
-lazy_static!(
+/// Parse a seed given as a decimal or a `0x` prefixed hexadecimal number
+/// 
+/// # Arguments
+/// 
+/// * `value` - Seed text, e.g. `42` or `0x2a`
 
-    pub static ref RNG: RandomGenerator = {
+pub fn parse_seed(value: &str) -> Result<u64, String> {
 
-        let args = args().collect::<Vec<String>>();
+    let value = value.trim();
 
-        if args[1] == "ws" {
-            RngGenerator::new(SeedableRng::from_seed(SEED))
-        }
+    let parsed = match value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => value.parse::<u64>(),
+    };
 
-        else if args[1] == "ns" { 
-            RngGenerator::new(StdRng::from_entropy())
-        }
+    parsed.map_err(|e| format!("invalid seed '{}': {}", value, e))
+}
+
+/// Format a seed as it's recorded in config files and reports
+
+pub fn format_seed(seed: u64) -> String {
+    format!("{:#018x}", seed)
+}
 
-        else {
-            panic!("Invalid argument")
+/// Serde representation of the seed
+/// 
+/// TOML integers are signed, so the seed is written as a hex
+/// string and read back either from a string or an integer
+
+pub mod seed_serde {
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum SeedValue {
+        Number(u64),
+        Text(String),
+    }
+
+    pub fn serialize<S: Serializer>(seed: &Option<u64>, serializer: S) -> Result<S::Ok, S::Error> {
+        match seed {
+            Some(seed) => serializer.serialize_str(&super::format_seed(*seed)),
+            None => serializer.serialize_none(),
         }
-    };
-);
+    }
 
-pub fn random() -> MutexGuard<'static, StdRng> {
-    return RNG.lock().unwrap()
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+        match Option::<SeedValue>::deserialize(deserializer)? {
+            Some(SeedValue::Number(seed)) => Ok(Some(seed)),
+            Some(SeedValue::Text(text)) => super::parse_seed(&text).map(Some).map_err(serde::de::Error::custom),
+            None => Ok(None),
+        }
+    }
 }
@@ -0,0 +1,202 @@
+use std::fs::File;
+use std::path::Path;
+use std::fmt::Write as _;
+use std::io::{self, BufWriter, Write};
+
+use crate::frame::Frame;
+use crate::position::Point;
+use crate::topology::Topology;
+
+/// Side of a board cell in pixels
+pub const CELL_SIZE: usize = 16;
+
+const BACKGROUND: [u8; 3] = [255, 255, 255];
+const GRID: [u8; 3] = [221, 221, 221];
+const GOAL: [u8; 3] = [232, 245, 233];
+const WALL: [u8; 3] = [68, 68, 68];
+const STRIPE: [u8; 3] = [255, 255, 255];
+
+/// Space between the border of a cell and its entity
+const INSET: usize = 2;
+
+/// Rect struct
+///
+/// Filled rectangle of an image, in pixels
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rect {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+    pub color: [u8; 3],
+}
+
+/// Size of the image of a frame (width, height) in pixels
+
+pub fn size(frame: &Frame) -> (usize, usize) {
+
+    let (rows, cols) = frame.board.dimensions;
+    let width = cols * CELL_SIZE + 1;
+
+    match frame.board.topology {
+        Topology::Hex => (width + CELL_SIZE / 2, rows * CELL_SIZE + 1),
+        _ => (width, rows * CELL_SIZE + 1),
+    }
+}
+
+/// Rectangles that draw a frame, in painting order
+///
+/// Like the terminal view every entity fills its cell with its color and
+/// killers have a white vertical stripe in the middle, the goal cells are
+/// tinted green and walls are dark gray. On a hex board the odd rows are
+/// shifted half a cell to the right
+
+pub fn shapes(frame: &Frame) -> Vec<Rect> {
+
+    let (rows, cols) = frame.board.dimensions;
+    let (width, height) = size(frame);
+    let occupancy = frame.occupancy();
+
+    let mut rects = vec![
+        Rect { x: 0, y: 0, width, height, color: GRID },
+    ];
+
+    for y in 0..rows {
+
+        let shift = match frame.board.is_shifted(y) {
+            true => CELL_SIZE / 2,
+            false => 0,
+        };
+
+        // Media celda vacía al inicio (o al final) de las filas de un tablero hexagonal
+
+        if frame.board.topology == Topology::Hex {
+            let x = if shift > 0 { 1 } else { cols * CELL_SIZE + 1 };
+            rects.push(Rect { x, y: y * CELL_SIZE + 1, width: CELL_SIZE / 2 - 1, height: CELL_SIZE - 1, color: BACKGROUND });
+        }
+
+        for x in 0..cols {
+
+            let point = Point::new(x as isize, y as isize);
+
+            let color = match frame.board.is_wall(point) {
+                true => WALL,
+                false if frame.board.is_goal(point) => GOAL,
+                false => BACKGROUND,
+            };
+
+            let (px, py) = (x * CELL_SIZE + 1 + shift, y * CELL_SIZE + 1);
+
+            rects.push(Rect { x: px, y: py, width: CELL_SIZE - 1, height: CELL_SIZE - 1, color });
+
+            let Some(cell) = occupancy.get(point).map(|i| &frame.cells[i]) else { continue };
+
+            let side = CELL_SIZE - 1 - 2 * INSET;
+            let color = [cell.color.r, cell.color.g, cell.color.b];
+
+            rects.push(Rect { x: px + INSET, y: py + INSET, width: side, height: side, color });
+
+            if cell.killer {
+                rects.push(Rect { x: px + INSET + side / 3, y: py + INSET, width: side - 2 * (side / 3), height: side, color: STRIPE });
+            }
+        }
+    }
+
+    rects
+}
+
+/// Draw a frame as an SVG document
+
+pub fn svg(frame: &Frame) -> String {
+
+    let (width, height) = size(frame);
+
+    let mut svg = String::new();
+
+    writeln!(svg, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" shape-rendering="crispEdges">"#, w = width, h = height).unwrap();
+    writeln!(svg, "<title>Generation {}, movement {}</title>", frame.generation, frame.iteration).unwrap();
+
+    for rect in shapes(frame) {
+        let [r, g, b] = rect.color;
+        writeln!(svg, r##"<rect x="{}" y="{}" width="{}" height="{}" fill="#{:02x}{:02x}{:02x}"/>"##, rect.x, rect.y, rect.width, rect.height, r, g, b).unwrap();
+    }
+
+    svg.push_str("</svg>\n");
+
+    svg
+}
+
+/// Draw a frame as RGB pixels, row by row
+
+pub fn pixels(frame: &Frame) -> Vec<u8> {
+
+    let (width, height) = size(frame);
+
+    let mut pixels = vec![0; width * height * 3];
+
+    for rect in shapes(frame) {
+        for y in rect.y..rect.y + rect.height {
+
+            let start = (y * width + rect.x) * 3;
+
+            for pixel in pixels[start..start + rect.width * 3].chunks_exact_mut(3) {
+                pixel.copy_from_slice(&rect.color);
+            }
+        }
+    }
+
+    pixels
+}
+
+pub fn write_svg(path: &Path, frame: &Frame) -> io::Result<()> {
+    std::fs::write(path, svg(frame))
+}
+
+pub fn write_png(path: &Path, frame: &Frame) -> io::Result<()> {
+
+    let (width, height) = size(frame);
+
+    let mut encoder = png::Encoder::new(BufWriter::new(File::create(path)?), width as u32, height as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut writer = encoder.write_header().map_err(io::Error::other)?;
+    writer.write_image_data(&pixels(frame)).map_err(io::Error::other)?;
+    writer.finish().map_err(io::Error::other)
+}
+
+/// Write the frames as an animated GIF that loops forever
+///
+/// # Arguments
+///
+/// * `path` - GIF file
+/// * `frames` - Frames of the animation, all of the same board
+/// * `frame_delay` - Delay between frames in milliseconds (GIF
+///   delays have a resolution of 10 ms)
+
+pub fn write_gif(path: &Path, frames: &[Frame], frame_delay: u64) -> io::Result<()> {
+
+    let Some(first) = frames.first() else {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "no frames to write"))
+    };
+
+    let (width, height) = size(first);
+
+    let mut writer = BufWriter::new(File::create(path)?);
+
+    {
+        let mut encoder = gif::Encoder::new(&mut writer, width as u16, height as u16, &[]).map_err(io::Error::other)?;
+        encoder.set_repeat(gif::Repeat::Infinite).map_err(io::Error::other)?;
+
+        for frame in frames {
+
+            let mut image = gif::Frame::from_rgb_speed(width as u16, height as u16, &pixels(frame), 10);
+            image.delay = (frame_delay / 10).clamp(1, u16::MAX as u64) as u16;
+
+            encoder.write_frame(&image).map_err(io::Error::other)?;
+        }
+    }
+
+    writer.flush()
+}
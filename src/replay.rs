@@ -0,0 +1,318 @@
+use std::fs::File;
+use std::path::Path;
+use std::str::FromStr;
+use std::collections::HashMap;
+use std::io::{self, BufReader};
+
+use uuid::Uuid;
+use colored::CustomColor;
+use serde::{Deserialize, Serialize};
+
+use crate::utils;
+use crate::board::Board;
+use crate::entity::Entity;
+use crate::position::Point;
+use crate::frame::{Cell, Frame};
+use crate::stats::GenerationStats;
+use crate::config::SimulationConfig;
+use crate::observer::SimulationObserver;
+
+/// Replay struct
+///
+/// Content of a `.rpl` file: the recorded generations of a run, each one
+/// stored as the initial entities plus the moves and deaths of every
+/// iteration, the boards are rebuilt with `GenerationReplay::frames`
+///
+/// # Attributes
+///
+/// * `config` - Configuration of the recorded run
+/// * `generations` - Recorded generations in order
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    pub config: SimulationConfig,
+    pub generations: Vec<GenerationReplay>,
+}
+
+/// GenerationReplay struct
+///
+/// # Attributes
+///
+/// * `generation` - Generation number
+/// * `converged` - Whether the generation converged
+/// * `entities` - Entities at the start of the generation, the
+///   steps refer to them by index
+/// * `iterations` - Steps of every iteration
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GenerationReplay {
+    pub generation: usize,
+    pub converged: bool,
+    pub entities: Vec<ReplayEntity>,
+    pub iterations: Vec<Steps>,
+}
+
+/// Initial state of a recorded entity
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReplayEntity {
+    pub position: Point,
+    pub killer: bool,
+    pub color: [u8; 3],
+}
+
+/// Steps struct
+///
+/// Changes of one iteration, `moves` holds the entity index and its new
+/// position (x, y) and `deaths` the index of the entities killed
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Steps {
+    pub moves: Vec<(usize, isize, isize)>,
+    pub deaths: Vec<usize>,
+}
+
+impl Replay {
+
+    /// Find a recorded generation by its number
+
+    pub fn find(&self, generation: usize) -> Option<usize> {
+        self.generations.iter().position(|g| g.generation == generation)
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        utils::replace(path, |writer| Ok(serde_json::to_writer(writer, self)?))
+    }
+
+    /// Read a replay file, its configuration must be valid and the
+    /// steps can only refer to the entities of their generation
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+
+        let reader = BufReader::new(File::open(path)?);
+        let replay: Replay = serde_json::from_reader(reader)?;
+
+        replay.config.validate().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        for recorded in replay.generations.iter() {
+
+            let count = recorded.entities.len();
+
+            let mut indexes = recorded.iterations.iter()
+                .flat_map(|steps| steps.moves.iter().map(|m| m.0).chain(steps.deaths.iter().copied()));
+
+            if let Some(index) = indexes.find(|&i| i >= count) {
+                let message = format!("generation {} refers to entity {} but has {} entities", recorded.generation, index, count);
+                return Err(io::Error::new(io::ErrorKind::InvalidData, message))
+            }
+        }
+
+        Ok(replay)
+    }
+}
+
+impl GenerationReplay {
+
+    /// Rebuild the board of every iteration, the first
+    /// frame is the initial state (iteration 0)
+    ///
+    /// # Arguments
+    ///
+    /// * `board` - Board of the recorded run
+
+    pub fn frames(&self, board: &Board) -> Vec<Frame> {
+
+        let mut positions: Vec<Point> = self.entities.iter().map(|e| e.position).collect();
+        let mut alive = vec![true; self.entities.len()];
+
+        let frame = |iteration: usize, positions: &[Point], alive: &[bool]| {
+
+            let cells = self.entities.iter().enumerate()
+                .filter(|(i, _)| alive[*i])
+                .map(|(i, e)| Cell {
+                    position: positions[i],
+                    killer: e.killer,
+                    color: CustomColor::new(e.color[0], e.color[1], e.color[2]),
+                })
+                .collect();
+
+            Frame { generation: self.generation, iteration, board: board.clone(), cells }
+        };
+
+        let mut frames = vec![frame(0, &positions, &alive)];
+
+        for (i, steps) in self.iterations.iter().enumerate() {
+
+            for &(entity, x, y) in steps.moves.iter() {
+                positions[entity] = Point::new(x, y);
+            }
+
+            for &entity in steps.deaths.iter() {
+                alive[entity] = false;
+            }
+
+            frames.push(frame(i + 1, &positions, &alive));
+        }
+
+        frames
+    }
+}
+
+/// ReplayGenerations enum
+///
+/// Generations written to the replay file, the converged (or the last)
+/// generation is always written
+///
+/// * `All` - Every generation
+/// * `Only` - The given generation numbers
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReplayGenerations {
+    All,
+    Only(Vec<usize>),
+}
+
+impl Default for ReplayGenerations {
+
+    fn default() -> Self {
+        ReplayGenerations::Only(Vec::new())
+    }
+}
+
+impl ReplayGenerations {
+
+    pub fn contains(&self, generation: usize) -> bool {
+        match self {
+            ReplayGenerations::All => true,
+            ReplayGenerations::Only(generations) => generations.contains(&generation),
+        }
+    }
+}
+
+impl FromStr for ReplayGenerations {
+
+    type Err = String;
+
+    /// Parse `all` or a comma separated list of generation numbers
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+
+        if s == "all" {
+            return Ok(ReplayGenerations::All)
+        }
+
+        s.split(',')
+            .map(|g| g.trim().parse::<usize>().map_err(|_| format!("invalid generation '{}'", g)))
+            .collect::<Result<Vec<usize>, String>>()
+            .map(ReplayGenerations::Only)
+    }
+}
+
+/// ReplayRecorder struct
+///
+/// Observer that records the chosen generations, the last generation
+/// seen is kept until the end in case it isn't one of them
+
+pub struct ReplayRecorder {
+    replay: Replay,
+    generations: ReplayGenerations,
+    current: Option<GenerationReplay>,
+    last: Option<GenerationReplay>,
+    index: HashMap<Uuid, usize>,
+}
+
+impl ReplayRecorder {
+
+    pub fn new(config: &SimulationConfig, generations: ReplayGenerations) -> Self {
+        ReplayRecorder {
+            replay: Replay { config: config.clone(), generations: Vec::new() },
+            generations,
+            current: None,
+            last: None,
+            index: HashMap::new(),
+        }
+    }
+
+    /// Get the replay with every recorded generation, an unfinished
+    /// generation is included as it is
+
+    pub fn finish(mut self) -> Replay {
+
+        self.replay.generations.extend(self.last.take());
+        self.replay.generations.extend(self.current.take());
+
+        self.replay
+    }
+
+    /// Index of an entity and the steps of the current iteration
+
+    fn steps(&mut self, entity: &Entity) -> Option<(usize, &mut Steps)> {
+        let index = *self.index.get(&entity.id)?;
+        let steps = self.current.as_mut()?.iterations.last_mut()?;
+        Some((index, steps))
+    }
+
+    fn death(&mut self, entity: &Entity) {
+        if let Some((index, steps)) = self.steps(entity) {
+            steps.deaths.push(index);
+        }
+    }
+}
+
+impl SimulationObserver for ReplayRecorder {
+
+    fn on_generation_start(&mut self, generation: usize, entities: &[Entity]) {
+
+        self.index = entities.iter().enumerate().map(|(i, e)| (e.id, i)).collect();
+
+        let entities = entities.iter()
+            .map(|e| ReplayEntity {
+                position: e.get_position(),
+                killer: e.killer,
+                color: [e.color.r, e.color.g, e.color.b],
+            })
+            .collect();
+
+        self.current = Some(GenerationReplay { generation, converged: false, entities, iterations: Vec::new() });
+    }
+
+    fn on_iteration_start(&mut self, _generation: usize, _iteration: usize) {
+        if let Some(current) = &mut self.current {
+            current.iterations.push(Steps::default());
+        }
+    }
+
+    fn on_move(&mut self, entity: &Entity, _from: Point, to: Point) {
+        if let Some((index, steps)) = self.steps(entity) {
+            steps.moves.push((index, to.x, to.y));
+        }
+    }
+
+    fn on_kill(&mut self, _killer: &Entity, victim: &Entity) {
+        self.death(victim);
+    }
+
+    fn on_mutual_kill(&mut self, first: &Entity, second: &Entity) {
+        self.death(first);
+        self.death(second);
+    }
+
+    fn on_fall(&mut self, entity: &Entity) {
+        self.death(entity);
+    }
+
+    fn on_generation_end(&mut self, stats: &GenerationStats) {
+
+        let Some(mut current) = self.current.take() else { return };
+
+        current.converged = stats.converged;
+
+        match self.generations.contains(current.generation) || stats.converged {
+            true => {
+                self.last = None;
+                self.replay.generations.push(current);
+            },
+            false => self.last = Some(current),
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use std::io::{self, Write};
+use std::path::Path;
+use std::fmt::Write as _;
+
+use crate::utils;
+use crate::render;
+use crate::random;
+use crate::frame::Frame;
+use crate::genome::Genome;
+use crate::export::RunMetadata;
+use crate::stats::GenerationStats;
+use crate::chart::{self, ChartOptions};
+use crate::topology::Topology;
+
+const STYLE: &str = "
+body { font-family: sans-serif; margin: 2em auto; max-width: 1000px; color: #222; }
+h1, h2 { font-weight: normal; }
+table { border-collapse: collapse; margin-bottom: 1em; }
+td, th { border-bottom: 1px solid #ddd; padding: 4px 12px; text-align: left; }
+pre { background: #f6f6f6; padding: 1em; }
+.charts svg, .genomes svg { max-width: 100%; height: auto; }
+.genomes { display: flex; flex-wrap: wrap; gap: 1em; }
+.genome { border: 1px solid #ddd; padding: 0.5em; }
+";
+
+/// Report struct
+///
+/// Content of the HTML report of a finished run
+///
+/// # Attributes
+///
+/// * `metadata` - Seed, configuration and duration of the run
+/// * `generations` - Statistics of every generation
+/// * `genomes` - Best genomes of the run, from the highest fitness
+/// * `frame` - Last frame of the converged (or last) generation
+
+pub struct Report<'a> {
+    pub metadata: &'a RunMetadata,
+    pub generations: &'a [GenerationStats],
+    pub genomes: &'a [Genome],
+    pub frame: Option<&'a Frame>,
+}
+
+impl Report<'_> {
+
+    /// Build the HTML document, the charts and the board are inline
+    /// SVG so the file doesn't depend on anything else
+
+    pub fn html(&self) -> io::Result<String> {
+
+        let metadata = self.metadata;
+        let seed = metadata.config.seed.map(random::format_seed).unwrap_or_default();
+
+        let mut html = String::new();
+
+        writeln!(html, "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">").unwrap();
+        writeln!(html, "<title>Genetic run {}</title>\n<style>{}</style>\n</head>\n<body>", seed, STYLE).unwrap();
+        writeln!(html, "<h1>Genetic run {}</h1>", seed).unwrap();
+
+        html.push_str("<h2>Summary</h2>\n");
+        html.push_str(&self.summary());
+
+        html.push_str("<h2>Configuration</h2>\n");
+        writeln!(html, "<pre>{}</pre>", escape(&metadata.config.to_toml())).unwrap();
+
+        if let Some(frame) = self.frame {
+
+            let title = match metadata.converged {
+                true => "Converged generation",
+                false => "Last generation",
+            };
+
+            writeln!(html, "<h2>{} (generation {}, movement {})</h2>", title, frame.generation, frame.iteration).unwrap();
+            html.push_str(&render::svg(frame));
+        }
+
+        html.push_str("<h2>Best genomes</h2>\n<div class=\"genomes\">\n");
+
+        if self.genomes.is_empty() {
+            html.push_str("<p>No entity reached the goal.</p>\n");
+        }
+
+        for genome in self.genomes {
+            html.push_str(&genome_card(genome, metadata.config.topology));
+        }
+
+        html.push_str("</div>\n<h2>Statistics</h2>\n<div class=\"charts\">\n");
+
+        for series in chart_series(self.generations) {
+
+            let options = ChartOptions { title: series.join(", "), size: (960, 360), ..ChartOptions::default() };
+
+            html.push_str(&chart::svg(self.generations, &series, &options)?);
+            html.push('\n');
+        }
+
+        html.push_str("</div>\n</body>\n</html>\n");
+
+        Ok(html)
+    }
+
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        let html = self.html()?;
+        utils::replace(path, |writer| writer.write_all(html.as_bytes()))
+    }
+
+    fn summary(&self) -> String {
+
+        let metadata = self.metadata;
+        let last = self.generations.last();
+
+        let best = self.generations.iter().filter_map(|s| s.best_fitness).max();
+        let murders: usize = self.generations.iter().map(|s| s.murders).sum();
+
+        let rows = [
+            ("Seed", metadata.seed.clone()),
+            ("Started at (unix time)", metadata.started_at.to_string()),
+            ("Wall clock", format!("{:.2} s", metadata.wall_clock_seconds)),
+            ("Converged", metadata.converged.to_string()),
+            ("Generations run", self.generations.len().to_string()),
+            ("Winners on the last generation", last.map(|s| s.winners.to_string()).unwrap_or_default()),
+            ("Best fitness", best.map(|f| f.to_string()).unwrap_or("-".to_string())),
+            ("Total murders", murders.to_string()),
+            ("Diversity on the last generation", last.map(|s| format!("{:.4}", s.diversity)).unwrap_or_default()),
+        ];
+
+        let mut table = String::from("<table>\n");
+
+        for (name, value) in rows {
+            writeln!(table, "<tr><th>{}</th><td>{}</td></tr>", name, escape(&value)).unwrap();
+        }
+
+        table.push_str("</table>\n");
+        table
+    }
+}
+
+/// Series drawn in the report, one chart per scalar series
+/// plus one for the gene means and one for the variances
+
+fn chart_series(generations: &[GenerationStats]) -> Vec<Vec<String>> {
+
+    let genes = generations.first().map(|s| s.gene_means.len()).unwrap_or(0);
+
+    let mut series: Vec<Vec<String>> = GenerationStats::SERIES.iter()
+        .map(|key| vec![key.to_string()])
+        .collect();
+
+    series.push((0..genes).map(|i| format!("gene_mean_{}", i)).collect());
+    series.push((0..genes).map(|i| format!("gene_variance_{}", i)).collect());
+
+    series.retain(|keys| !keys.is_empty());
+    series
+}
+
+/// Card of a genome with a bar per direction weight
+
+fn genome_card(genome: &Genome, topology: Topology) -> String {
+
+    const WIDTH: usize = 220;
+    const BAR: usize = 18;
+    const LABEL: usize = 80;
+
+    let height = genome.values.len() * BAR;
+    let max = genome.values.iter().cloned().fold(f64::EPSILON, f64::max);
+
+    let mut svg = format!(r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" font-size="12">"#, WIDTH, height);
+
+    for (i, (name, value)) in topology.names().iter().zip(genome.values.iter()).enumerate() {
+
+        let y = i * BAR;
+        let width = ((WIDTH - LABEL - 40) as f64 * value / max).round() as usize;
+
+        write!(svg, r#"<text x="0" y="{}">{}</text>"#, y + 13, name).unwrap();
+        write!(svg, r##"<rect x="{}" y="{}" width="{}" height="{}" fill="#4878a8"/>"##, LABEL, y + 3, width, BAR - 6).unwrap();
+        write!(svg, r#"<text x="{}" y="{}">{:.3}</text>"#, LABEL + width + 4, y + 13, value).unwrap();
+    }
+
+    svg.push_str("</svg>");
+
+    format!(
+        "<div class=\"genome\"><div>fitness {} &middot; generation {} &middot; {}</div>{}</div>\n",
+        genome.fitness,
+        genome.generation,
+        if genome.killer { "killer" } else { "walker" },
+        svg,
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
@@ -0,0 +1,216 @@
+use serde::{Deserialize, Serialize};
+
+use crate::entity::Entity;
+use crate::poblation::Poblation;
+
+/// GenerationStats struct
+///
+/// Summary of a finished generation
+///
+/// # Attributes
+///
+/// * `generation` - Generation number (starting at 1)
+/// * `sample` - Entities of the generation
+/// * `murders` - Entities killed during the generation
+/// * `winners` - Entities that reached the goal
+/// * `converged` - Whether the generation converged
+/// * `best_fitness` - Highest fitness of the goal reachers (fewest moves)
+/// * `mean_fitness` - Mean fitness of the goal reachers
+/// * `worst_fitness` - Lowest fitness of the goal reachers
+/// * `killer_ratio` - Fraction of killers in the generation
+/// * `survivors` - Entities alive at the end of the generation
+/// * `mean_steps_to_goal` - Mean iteration at which the goal was reached
+/// * `blocked_moves` - Moves blocked by a wall or the edge of the board
+/// * `falls` - Entities that fell off a lethal edge of the board
+/// * `gene_means` - Mean of each direction value
+/// * `gene_variances` - Variance of each direction value
+/// * `diversity` - Mean euclidean distance between every pair of genomes
+///
+/// The fitness and steps fields are None if no entity reached the goal
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GenerationStats {
+    pub generation: usize,
+    pub sample: usize,
+    pub murders: usize,
+    pub winners: usize,
+    pub converged: bool,
+    pub best_fitness: Option<usize>,
+    pub mean_fitness: Option<f64>,
+    pub worst_fitness: Option<usize>,
+    pub killer_ratio: f64,
+    pub survivors: usize,
+    pub mean_steps_to_goal: Option<f64>,
+    pub blocked_moves: usize,
+    #[serde(default)]
+    pub falls: usize,
+    pub gene_means: Vec<f64>,
+    pub gene_variances: Vec<f64>,
+    pub diversity: f64,
+}
+
+impl GenerationStats {
+
+    /// Scalar series that can be plotted, the per direction series
+    /// are named `gene_mean_<i>` and `gene_variance_<i>`
+
+    pub const SERIES: [&'static str; 11] = [
+        "murders",
+        "winners",
+        "best_fitness",
+        "mean_fitness",
+        "worst_fitness",
+        "killer_ratio",
+        "survivors",
+        "mean_steps_to_goal",
+        "blocked_moves",
+        "falls",
+        "diversity",
+    ];
+
+    /// Compute the statistics of the current generation of a Poblation,
+    /// it must be called before the goal list is consumed by the selection
+    ///
+    /// # Arguments
+    ///
+    /// * `poblation` - Poblation that ran every iteration of the generation
+    /// * `converged` - Whether the generation converged
+
+    pub fn compute(poblation: &Poblation, converged: bool) -> Self {
+
+        let entities = &poblation.entities;
+        let on_goal = &poblation.on_goal_entities;
+
+        let fitness = on_goal.iter().map(|e| e.fitness);
+
+        let steps = &poblation.steps_to_goal;
+        let mean_steps_to_goal = (!steps.is_empty())
+            .then(|| steps.iter().sum::<usize>() as f64 / steps.len() as f64);
+
+        let (gene_means, gene_variances) = gene_moments(entities);
+
+        GenerationStats {
+            generation: poblation.actual_gen,
+            sample: entities.len(),
+            murders: poblation.murders,
+            winners: on_goal.len(),
+            converged,
+            best_fitness: fitness.clone().max(),
+            mean_fitness: (!on_goal.is_empty())
+                .then(|| fitness.clone().sum::<usize>() as f64 / on_goal.len() as f64),
+            worst_fitness: fitness.min(),
+            killer_ratio: entities.iter().filter(|e| e.is_killer()).count() as f64 / entities.len() as f64,
+            survivors: entities.iter().filter(|e| e.alive).count(),
+            mean_steps_to_goal,
+            blocked_moves: poblation.blocked_moves,
+            falls: poblation.falls,
+            gene_means,
+            gene_variances,
+            diversity: diversity(entities),
+        }
+    }
+
+    /// Check if a key names a series of `GenerationStats::value`
+
+    pub fn is_series(key: &str) -> bool {
+
+        if GenerationStats::SERIES.contains(&key) {
+            return true
+        }
+
+        let index = key.strip_prefix("gene_mean_").or_else(|| key.strip_prefix("gene_variance_"));
+
+        index.is_some_and(|i| i.parse::<usize>().is_ok())
+    }
+
+    /// Get the value of a series for this generation
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Series name, "murders" and "winners" are
+    ///   given as a fraction of the sample
+    ///
+    /// # Returns
+    ///
+    /// * `Option<f64>` - None if the key is unknown or the value is
+    ///   missing on this generation (e.g. fitness without winners)
+
+    pub fn value(&self, key: &str) -> Option<f64> {
+
+        let sample = self.sample as f64;
+
+        match key {
+            "murders" => Some(self.murders as f64 / sample),
+            "winners" => Some(self.winners as f64 / sample),
+            "best_fitness" => self.best_fitness.map(|f| f as f64),
+            "mean_fitness" => self.mean_fitness,
+            "worst_fitness" => self.worst_fitness.map(|f| f as f64),
+            "killer_ratio" => Some(self.killer_ratio),
+            "survivors" => Some(self.survivors as f64),
+            "mean_steps_to_goal" => self.mean_steps_to_goal,
+            "blocked_moves" => Some(self.blocked_moves as f64),
+            "falls" => Some(self.falls as f64),
+            "diversity" => Some(self.diversity),
+            _ => {
+
+                if let Some(i) = key.strip_prefix("gene_mean_") {
+                    return i.parse::<usize>().ok().and_then(|i| self.gene_means.get(i).copied())
+                }
+
+                if let Some(i) = key.strip_prefix("gene_variance_") {
+                    return i.parse::<usize>().ok().and_then(|i| self.gene_variances.get(i).copied())
+                }
+
+                None
+            }
+        }
+    }
+}
+
+/// Mean and variance of every direction value
+
+fn gene_moments(entities: &[Entity]) -> (Vec<f64>, Vec<f64>) {
+
+    let genes = entities.first().map(|e| e.values.len()).unwrap_or(0);
+    let n = entities.len() as f64;
+
+    let mut means = vec![0.0; genes];
+    let mut variances = vec![0.0; genes];
+
+    for entity in entities.iter() {
+        for (mean, value) in means.iter_mut().zip(entity.values.iter()) {
+            *mean += value / n;
+        }
+    }
+
+    for entity in entities.iter() {
+        for ((variance, mean), value) in variances.iter_mut().zip(means.iter()).zip(entity.values.iter()) {
+            *variance += (value - mean).powi(2) / n;
+        }
+    }
+
+    (means, variances)
+}
+
+/// Mean euclidean distance between every pair of genomes
+
+fn diversity(entities: &[Entity]) -> f64 {
+
+    let mut total = 0.0;
+    let mut pairs = 0;
+
+    for (i, a) in entities.iter().enumerate() {
+        for b in entities[i + 1..].iter() {
+
+            let distance = a.values.iter().zip(b.values.iter())
+                .map(|(x, y)| (x - y).powi(2))
+                .sum::<f64>()
+                .sqrt();
+
+            total += distance;
+            pairs += 1;
+        }
+    }
+
+    if pairs == 0 { 0.0 } else { total / pairs as f64 }
+}
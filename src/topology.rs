@@ -0,0 +1,151 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::position::{Point, DIRECTIONS};
+
+const VON_NEUMANN: [Point; 4] = [
+    Point { x: 0, y: -1 },  // up
+    Point { x: 1, y: 0 },   // right
+    Point { x: 0, y: 1 },   // down
+    Point { x: -1, y: 0 },  // left
+];
+
+/// Hex directions of the even rows, the odd rows are shifted half a cell to the right
+const HEX_EVEN: [Point; 6] = [
+    Point { x: -1, y: -1 }, // up-left
+    Point { x: 0, y: -1 },  // up-right
+    Point { x: 1, y: 0 },   // right
+    Point { x: 0, y: 1 },   // down-right
+    Point { x: -1, y: 1 },  // down-left
+    Point { x: -1, y: 0 },  // left
+];
+
+const HEX_ODD: [Point; 6] = [
+    Point { x: 0, y: -1 },  // up-left
+    Point { x: 1, y: -1 },  // up-right
+    Point { x: 1, y: 0 },   // right
+    Point { x: 1, y: 1 },   // down-right
+    Point { x: 0, y: 1 },   // down-left
+    Point { x: -1, y: 0 },  // left
+];
+
+const STAY: Point = Point { x: 0, y: 0 };
+
+/// Topology enum
+///
+/// Neighbourhood of a cell, the moves an entity can make. An entity
+/// has one direction value (gene) per move of the topology
+///
+/// * `VonNeumann` - 4 moves: up, right, down and left
+/// * `Moore` - 8 moves, the orthogonal and diagonal neighbours
+/// * `MooreStay` - The 8 Moore moves plus staying in the cell
+/// * `Hex` - 6 moves on a hexagonal grid, the odd rows are
+///   shifted half a cell to the right
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Topology {
+    VonNeumann,
+    #[default]
+    Moore,
+    MooreStay,
+    Hex,
+}
+
+impl Topology {
+
+    pub const ALL: [Topology; 4] = [Topology::VonNeumann, Topology::Moore, Topology::MooreStay, Topology::Hex];
+
+    /// Number of moves, and of direction values of an entity
+
+    pub fn genes(&self) -> usize {
+        self.names().len()
+    }
+
+    /// Topology of the entities with the given number of direction values
+
+    pub fn from_genes(genes: usize) -> Option<Self> {
+        Topology::ALL.into_iter().find(|t| t.genes() == genes)
+    }
+
+    /// Name of every move, in the order of the direction values
+
+    pub fn names(&self) -> &'static [&'static str] {
+        match self {
+            Topology::VonNeumann => &["up", "right", "down", "left"],
+            Topology::Moore => &["up-left", "up", "up-right", "right", "down-right", "down", "down-left", "left"],
+            Topology::MooreStay => &["up-left", "up", "up-right", "right", "down-right", "down", "down-left", "left", "stay"],
+            Topology::Hex => &["up-left", "up-right", "right", "down-right", "down-left", "left"],
+        }
+    }
+
+    /// Index of the mirrored move, left and right are swapped on the
+    /// horizontal axis and up and down on the vertical one
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index of the move (`names`)
+    /// * `flip_x` - Mirror the horizontal component
+    /// * `flip_y` - Mirror the vertical component
+
+    pub fn mirror(&self, index: usize, flip_x: bool, flip_y: bool) -> usize {
+
+        let swap = |part: &str| match part {
+            "left" if flip_x => "right",
+            "right" if flip_x => "left",
+            "up" if flip_y => "down",
+            "down" if flip_y => "up",
+            part => part,
+        }.to_string();
+
+        let name = self.names()[index].split('-').map(swap).collect::<Vec<String>>().join("-");
+
+        self.names().iter().position(|n| *n == name).unwrap_or(index)
+    }
+
+    /// Offset of a move from a cell
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - Current cell, the hex moves depend on the parity of its row
+    /// * `index` - Index of the move (`names`)
+
+    pub fn direction(&self, from: Point, index: usize) -> Point {
+        match self {
+            Topology::VonNeumann => VON_NEUMANN[index],
+            Topology::Moore => DIRECTIONS[index],
+            Topology::MooreStay => DIRECTIONS.get(index).copied().unwrap_or(STAY),
+            Topology::Hex if from.y.rem_euclid(2) == 0 => HEX_EVEN[index],
+            Topology::Hex => HEX_ODD[index],
+        }
+    }
+}
+
+impl fmt::Display for Topology {
+
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Topology::VonNeumann => write!(f, "von-neumann"),
+            Topology::Moore => write!(f, "moore"),
+            Topology::MooreStay => write!(f, "moore-stay"),
+            Topology::Hex => write!(f, "hex"),
+        }
+    }
+}
+
+impl FromStr for Topology {
+
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "von-neumann" => Ok(Topology::VonNeumann),
+            "moore" => Ok(Topology::Moore),
+            "moore-stay" => Ok(Topology::MooreStay),
+            "hex" => Ok(Topology::Hex),
+            _ => Err(format!("unknown topology '{}', expected von-neumann, moore, moore-stay or hex", s)),
+        }
+    }
+}
@@ -0,0 +1,359 @@
+use std::thread;
+use std::io::{self, stdout, Stdout, Write};
+use std::time::{Duration, Instant};
+
+use colored::*;
+use termion::event::Key;
+use termion::input::{Keys, TermRead};
+use termion::raw::{IntoRawMode, RawTerminal};
+use termion::screen::{AlternateScreen, IntoAlternateScreen};
+use termion::{async_stdin, clear, cursor, AsyncReader};
+
+use genetic::position::Point;
+use genetic::topology::Topology;
+use genetic::utils::trunc_uuid;
+use genetic::{Entity, Poblation};
+
+use crate::outputs::Outputs;
+
+const HELP: &str = "space pause  s step  +/- speed  k killers  tab select  esc deselect  q quit";
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+const SPARKLINE_WIDTH: usize = 40;
+
+/// Minimum time between two redraws of the generations that aren't animated
+const REFRESH: Duration = Duration::from_millis(50);
+
+/// Tui struct
+///
+/// Full-screen view of a running simulation: the board, a stats panel
+/// with the winners and murders sparklines and the genome of the
+/// selected entity. The generations multiple of `show_threshold` are
+/// animated with `frame_delay`, the rest run as fast as possible
+///
+/// # Attributes
+///
+/// * `screen` - Alternate screen in raw mode, restored when dropped
+/// * `keys` - Keys pressed, read without blocking
+/// * `paused` - Whether the simulation is paused
+/// * `frame_delay` - Delay between animated frames in milliseconds
+/// * `highlight` - Whether killers are drawn distinctly
+/// * `selected` - Identifier of the inspected entity
+
+pub struct Tui {
+    screen: AlternateScreen<RawTerminal<Stdout>>,
+    keys: Keys<AsyncReader>,
+    paused: bool,
+    frame_delay: u64,
+    highlight: bool,
+    selected: Option<uuid::Uuid>,
+}
+
+impl Tui {
+
+    /// Enter the full-screen view, None if stdout isn't a terminal
+
+    pub fn open(frame_delay: u64) -> Option<Self> {
+
+        let screen = stdout().into_raw_mode().ok()?.into_alternate_screen().ok()?;
+
+        Some(Tui {
+            screen,
+            keys: async_stdin().keys(),
+            paused: false,
+            frame_delay,
+            highlight: true,
+            selected: None,
+        })
+    }
+
+    /// Run the remaining generations until the simulation converges,
+    /// the generations run out or the user quits, the last screen stays
+    /// until `q` is pressed. It saves the checkpoints like `drive`
+    ///
+    /// # Returns
+    ///
+    /// * `io::Result<bool>` - Whether a generation converged, or the
+    ///   error of a checkpoint
+
+    pub fn run(mut self, poblation: &mut Poblation, outputs: &mut Outputs) -> io::Result<bool> {
+
+        // El tablero lo dibuja la TUI, no Poblation::show
+
+        let headless = std::mem::replace(&mut poblation.config.headless, true);
+
+        let converged = self.drive(poblation, outputs);
+
+        poblation.config.headless = headless;
+
+        converged
+    }
+
+    fn drive(&mut self, poblation: &mut Poblation, outputs: &mut Outputs) -> io::Result<bool> {
+
+        let mut next_frame = Instant::now();
+        let mut last_draw = Instant::now() - REFRESH;
+
+        let mut converged = false;
+        let mut finished = false;
+
+        write!(self.screen, "{}", cursor::Hide).unwrap();
+
+        loop {
+
+            let mut step = false;
+
+            while let Some(Ok(key)) = self.keys.next() {
+
+                match key {
+                    Key::Char('q') | Key::Ctrl('c') => {
+                        return Ok(converged)
+                    },
+                    Key::Char(' ') => self.paused = !self.paused,
+                    Key::Char('s') | Key::Right => {
+                        self.paused = true;
+                        step = true;
+                    },
+                    Key::Char('+') => self.frame_delay = (self.frame_delay / 2).max(1),
+                    Key::Char('-') => self.frame_delay = (self.frame_delay * 2).min(5000),
+                    Key::Char('k') => self.highlight = !self.highlight,
+                    Key::Char('\t') => self.select(poblation, 1),
+                    Key::BackTab => self.select(poblation, -1),
+                    Key::Esc => self.selected = None,
+                    _ => {},
+                }
+
+                // Las teclas se reflejan de inmediato aunque esté en pausa
+
+                last_draw = Instant::now() - REFRESH;
+            }
+
+            let animated = poblation.actual_gen.is_multiple_of(poblation.config.show_threshold);
+
+            let advance = !finished && match self.paused {
+                true => step,
+                false => !animated || Instant::now() >= next_frame,
+            };
+
+            if advance {
+
+                if poblation.step_iteration_with(&mut outputs.observers()) {
+
+                    converged = poblation.finish_generation_with(&mut outputs.observers());
+                    finished = converged || poblation.actual_gen > poblation.config.n_generations;
+
+                    if !converged {
+                        outputs.checkpoint(poblation)?;
+                    }
+                }
+
+                next_frame = Instant::now() + Duration::from_millis(self.frame_delay);
+            }
+
+            if (advance && (animated || self.paused)) || last_draw.elapsed() >= REFRESH {
+                self.draw(poblation, finished, converged);
+                last_draw = Instant::now();
+            }
+
+            if !advance || animated {
+                thread::sleep(Duration::from_millis(5));
+            }
+        }
+    }
+
+    /// Select the next (or previous) alive entity
+
+    fn select(&mut self, poblation: &Poblation, direction: isize) {
+
+        let alive: Vec<&Entity> = poblation.entities.iter().filter(|e| e.alive).collect();
+
+        if alive.is_empty() {
+            self.selected = None;
+            return
+        }
+
+        let current = self.selected.and_then(|id| alive.iter().position(|e| e.id == id));
+
+        let index = match current {
+            Some(i) => (i as isize + direction).rem_euclid(alive.len() as isize) as usize,
+            None if direction > 0 => 0,
+            None => alive.len() - 1,
+        };
+
+        self.selected = Some(alive[index].id);
+    }
+
+    fn draw(&mut self, poblation: &Poblation, finished: bool, converged: bool) {
+
+        let board = self.board(poblation);
+        let panel = self.panel(poblation, finished, converged);
+
+        let board_width = self.board_width(poblation);
+
+        let mut buffer = format!("{}", cursor::Goto(1, 1));
+
+        for i in 0..board.len().max(panel.len()) {
+
+            let left = board.get(i).map(String::as_str).unwrap_or("");
+            let padding = match i < board.len() {
+                true => 3,
+                false => board_width + 3,
+            };
+
+            buffer.push_str(left);
+            buffer.push_str(&" ".repeat(padding));
+            buffer.push_str(panel.get(i).map(String::as_str).unwrap_or(""));
+            buffer.push_str(&format!("{}\r\n", clear::UntilNewline));
+        }
+
+        buffer.push_str(&format!("\r\n{}{}", HELP.dimmed(), clear::AfterCursor));
+
+        write!(self.screen, "{}", buffer).unwrap();
+        self.screen.flush().unwrap();
+    }
+
+    /// Width of the board lines, hex boards have half a cell more
+
+    fn board_width(&self, poblation: &Poblation) -> usize {
+        match poblation.board.topology {
+            Topology::Hex => poblation.board.dimensions.1 * 2 + 3,
+            _ => poblation.board.dimensions.1 * 2 + 2,
+        }
+    }
+
+    /// Lines of the board, every cell is two characters wide
+
+    fn board(&self, poblation: &Poblation) -> Vec<String> {
+
+        let (rows, cols) = poblation.board.dimensions;
+
+        let border = format!("+{}+", "-".repeat(self.board_width(poblation) - 2));
+        let mut lines = vec![border.clone()];
+
+        for y in 0..rows {
+
+            let mut line = String::from("|");
+
+            let shifted = poblation.board.is_shifted(y);
+
+            if shifted {
+                line.push(' ');
+            }
+
+            for x in 0..cols {
+
+                let point = Point::new(x as isize, y as isize);
+
+                let glyph = match poblation.entity_at(point) {
+                    Some(e) if Some(e.id) == self.selected => "[]".custom_color(e.color).reversed(),
+                    Some(e) if e.killer && self.highlight => "><".custom_color(e.color).on_white(),
+                    Some(e) => "██".custom_color(e.color),
+                    None if poblation.board.is_wall(point) => "▓▓".bright_black(),
+                    None if poblation.board.is_goal(point) => "░░".bright_black(),
+                    None => "  ".normal(),
+                };
+
+                line.push_str(&glyph.to_string());
+            }
+
+            if poblation.board.topology == Topology::Hex && !shifted {
+                line.push(' ');
+            }
+
+            line.push('|');
+            lines.push(line);
+        }
+
+        lines.push(border);
+        lines
+    }
+
+    /// Lines of the stats panel and the inspector
+
+    fn panel(&self, poblation: &Poblation, finished: bool, converged: bool) -> Vec<String> {
+
+        let config = &poblation.config;
+        let entities = &poblation.entities;
+
+        let state = match (finished, converged, self.paused) {
+            (true, true, _) => "converged, q to quit".green().to_string(),
+            (true, false, _) => "didn't converge, q to quit".yellow().to_string(),
+            (false, _, true) => "paused".yellow().to_string(),
+            (false, _, false) => "running".green().to_string(),
+        };
+
+        let last = poblation.generations.last();
+
+        let mut lines = vec![
+            format!("{}  {}", "Genetic".bold(), state),
+            String::new(),
+            format!("Generation   {:>5} / {}", poblation.actual_gen.min(config.n_generations), config.n_generations),
+            format!("Iteration    {:>5} / {}", poblation.iteration, config.n_iterations),
+            format!("Alive        {:>5} / {}", entities.iter().filter(|e| e.alive).count(), entities.len()),
+            format!("On goal      {:>5} / {}", poblation.on_goal_entities.len(), poblation.goal_target()),
+            format!("Murders      {:>5}", poblation.murders),
+            format!("Falls        {:>5}", poblation.falls),
+            format!("Killers      {:>5}", entities.iter().filter(|e| e.killer).count()),
+            format!("Frame delay  {:>5} ms", self.frame_delay),
+            format!("Highlight    {:>5}", if self.highlight { "on" } else { "off" }),
+            String::new(),
+            format!("Last generation  best fitness {}", last.and_then(|s| s.best_fitness).map(|f| f.to_string()).unwrap_or("-".to_string())),
+            format!("Winners  {}", sparkline(poblation, "winners").green()),
+            format!("Murders  {}", sparkline(poblation, "murders").red()),
+            String::new(),
+        ];
+
+        let selected = self.selected.and_then(|id| entities.iter().find(|e| e.id == id));
+
+        match selected {
+            Some(entity) => lines.extend(inspect(entity, config.topology)),
+            None => lines.push("Tab selects an entity".dimmed().to_string()),
+        }
+
+        lines
+    }
+}
+
+impl Drop for Tui {
+
+    fn drop(&mut self) {
+        let _ = write!(self.screen, "{}", cursor::Show);
+        let _ = self.screen.flush();
+    }
+}
+
+/// Sparkline of the last generations of a series with values in [0, 1]
+
+fn sparkline(poblation: &Poblation, key: &str) -> String {
+
+    let values: Vec<f64> = poblation.generations.iter().filter_map(|s| s.value(key)).collect();
+    let start = values.len().saturating_sub(SPARKLINE_WIDTH);
+
+    values[start..].iter()
+        .map(|v| SPARKS[((v.clamp(0.0, 1.0) * 7.0).round()) as usize])
+        .collect()
+}
+
+/// Genome and fitness of an entity
+
+fn inspect(entity: &Entity, topology: Topology) -> Vec<String> {
+
+    let position = entity.position.map(|p| format!("({}, {})", p.x, p.y)).unwrap_or("-".to_string());
+
+    let mut lines = vec![
+        format!("Entity E{}", trunc_uuid(&entity.id).custom_color(entity.color).bold()),
+        format!("{}  fitness {}  {}",
+            if entity.killer { "killer".red() } else { "walker".normal() },
+            entity.fitness,
+            position,
+        ),
+    ];
+
+    for (name, value) in topology.names().iter().zip(entity.values.iter()) {
+
+        let bar = "█".repeat((value * 20.0).round() as usize);
+
+        lines.push(format!("  {:<10} {:.3} {}", name, value, bar.custom_color(entity.color)));
+    }
+
+    lines
+}
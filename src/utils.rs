@@ -1,27 +1,27 @@
 
-use uuid::Uuid;
+use uuid::{Builder, Uuid};
 use rand::RngCore;
 use colored::CustomColor;
 
 use termion::input::TermRead;
-use std::io::{stdin, stdout, Write};
+use std::fs::{self, File};
+use std::path::Path;
+use std::io::{self, stdin, stdout, BufWriter, Write};
 
-pub use crate::random::random;
-
-pub fn normalize(values: &Vec<f64>) -> Vec<f64> {
+pub fn normalize(values: &[f64]) -> Vec<f64> {
 
     let mut normalized: Vec<f64> = Vec::new();
 
     let sum = values.iter().sum::<f64>();
 
-    for i in 0..values.len() {
-        normalized.push(values[i] / sum)
+    for value in values.iter() {
+        normalized.push(value / sum)
     }
 
     normalized
 }
 
-pub fn cumulative(values: &Vec<f64>) -> Vec<f64> {
+pub fn cumulative(values: &[f64]) -> Vec<f64> {
 
     let mut cumulative = 0.0;
     let mut acc_values = Vec::new();
@@ -47,14 +47,23 @@ pub fn to_rgb(color: (f64, f64, f64)) -> CustomColor {
     )
 }
 
+/// Color of an entity, made of three of its direction values
+
+pub fn values_color(values: &[f64]) -> CustomColor {
+    let value = |i: usize| values[i % values.len()];
+    to_rgb((value(2), value(3), value(4)))
+}
+
 pub fn trunc_uuid(uuid: &Uuid) -> String {
-    return uuid.to_string()[..4].to_string()
+    uuid.to_string()[..4].to_string()
 }
 
-pub fn uuid() -> Uuid {
+/// Random (version 4) identifier drawn from the simulation generator
+
+pub fn uuid<R: RngCore>(rng: &mut R) -> Uuid {
     let mut bytes = [0u8; 16];
-    random().fill_bytes(&mut bytes);
-    Uuid::from_bytes(bytes)
+    rng.fill_bytes(&mut bytes);
+    Builder::from_random_bytes(bytes).into_uuid()
 }
 
 pub fn continue_prompt() {
@@ -67,3 +76,26 @@ pub fn continue_prompt() {
     stdin.keys().next();
 }
 
+
+/// Write a file through a temporary file next to it and a rename,
+/// the old content is kept until the new one is complete
+///
+/// # Arguments
+///
+/// * `path` - Path of the file
+/// * `write` - Writes the content, the writer is flushed afterwards
+
+pub fn replace<F>(path: &Path, write: F) -> io::Result<()>
+where
+    F: FnOnce(&mut BufWriter<File>) -> io::Result<()>,
+{
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+
+    let mut writer = BufWriter::new(File::create(&tmp)?);
+    write(&mut writer)?;
+    writer.flush()?;
+    drop(writer);
+
+    fs::rename(&tmp, path)
+}
@@ -0,0 +1,240 @@
+use std::thread;
+use std::time::{Duration, Instant};
+use std::io::{stdout, Write};
+
+use termion::event::Key;
+use termion::async_stdin;
+use termion::input::TermRead;
+use termion::raw::IntoRawMode;
+
+use genetic::board::Board;
+use genetic::frame::Frame;
+use genetic::replay::Replay;
+
+const HELP: &str = "space pause  \u{2190}/\u{2192} step  +/- speed  n/p generation  g jump  q quit";
+
+/// Viewer struct
+///
+/// Playback state of a replay file, only the frames of
+/// the generation on screen are rebuilt
+
+pub struct Viewer<'a> {
+    replay: &'a Replay,
+    generation: usize,
+    frames: Vec<Frame>,
+    iteration: usize,
+    paused: bool,
+    frame_delay: u64,
+    input: Option<String>,
+}
+
+impl<'a> Viewer<'a> {
+
+    /// Create a viewer on the recorded generation closest to `generation`
+    ///
+    /// # Arguments
+    ///
+    /// * `replay` - Replay with at least one generation
+    /// * `generation` - Generation number to start from
+    /// * `frame_delay` - Delay between frames in milliseconds
+
+    pub fn new(replay: &'a Replay, generation: usize, frame_delay: u64) -> Self {
+
+        let mut viewer = Viewer {
+            replay,
+            generation: 0,
+            frames: Vec::new(),
+            iteration: 0,
+            paused: false,
+            frame_delay,
+            input: None,
+        };
+
+        viewer.jump(generation);
+        viewer
+    }
+
+    /// Show the replay until it ends or the user quits, the keys only
+    /// work if stdout is a terminal, otherwise every frame is printed once
+
+    pub fn play(&mut self) {
+
+        let Ok(mut stdout) = stdout().into_raw_mode() else {
+            return self.print()
+        };
+
+        let mut keys = async_stdin().keys();
+
+        write!(stdout, "{}", termion::cursor::Hide).unwrap();
+
+        'frames: loop {
+
+            // En modo raw el salto de línea no vuelve al inicio
+
+            write!(stdout, "{}", self.draw().replace('\n', "\r\n")).unwrap();
+            stdout.flush().unwrap();
+
+            let deadline = Instant::now() + Duration::from_millis(self.frame_delay);
+
+            loop {
+
+                if let Some(Ok(key)) = keys.next() {
+
+                    if !self.handle(key) {
+                        break 'frames
+                    }
+
+                    continue 'frames
+                }
+
+                if !self.paused && Instant::now() >= deadline {
+                    self.paused = !self.forward();
+                    continue 'frames
+                }
+
+                thread::sleep(Duration::from_millis(5));
+            }
+        }
+
+        write!(stdout, "{}\r\n", termion::cursor::Show).unwrap();
+    }
+
+    /// Print every frame from the current one without waiting for keys
+
+    fn print(&mut self) {
+
+        loop {
+
+            print!("{}", self.draw());
+            thread::sleep(Duration::from_millis(self.frame_delay));
+
+            if !self.forward() {
+                break
+            }
+        }
+    }
+
+    /// Apply a key
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - False if the viewer must be closed
+
+    fn handle(&mut self, key: Key) -> bool {
+
+        // Mientras se escribe una generación solo se aceptan dígitos
+
+        if let Some(input) = &mut self.input {
+
+            match key {
+                Key::Char(c) if c.is_ascii_digit() => input.push(c),
+                Key::Backspace => { input.pop(); },
+                Key::Char('\n') => {
+                    if let Ok(generation) = input.parse::<usize>() {
+                        self.jump(generation);
+                    }
+                    self.input = None;
+                },
+                Key::Esc => self.input = None,
+                _ => {},
+            }
+
+            return true
+        }
+
+        match key {
+            Key::Char('q') | Key::Esc | Key::Ctrl('c') => return false,
+            Key::Char(' ') => self.paused = !self.paused,
+            Key::Right | Key::Char('.') => {
+                self.paused = true;
+                self.forward();
+            },
+            Key::Left | Key::Char(',') => {
+                self.paused = true;
+                self.back();
+            },
+            Key::Char('+') => self.frame_delay = (self.frame_delay / 2).max(1),
+            Key::Char('-') => self.frame_delay = (self.frame_delay * 2).min(5000),
+            Key::Char('n') => self.load((self.generation + 1).min(self.replay.generations.len() - 1)),
+            Key::Char('p') => self.load(self.generation.saturating_sub(1)),
+            Key::Char('g') => self.input = Some(String::new()),
+            _ => {},
+        }
+
+        true
+    }
+
+    /// Go to the recorded generation closest to the given number
+
+    fn jump(&mut self, generation: usize) {
+
+        let index = (0..self.replay.generations.len())
+            .min_by_key(|&i| self.replay.generations[i].generation.abs_diff(generation))
+            .unwrap();
+
+        self.load(index);
+    }
+
+    fn load(&mut self, index: usize) {
+        self.generation = index;
+        self.frames = self.replay.generations[index].frames(&Board::new(&self.replay.config));
+        self.iteration = 0;
+    }
+
+    /// Go to the next frame, at the end of a generation
+    /// the next recorded one starts
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - False if it was the last frame of the replay
+
+    fn forward(&mut self) -> bool {
+
+        if self.iteration + 1 < self.frames.len() {
+            self.iteration += 1;
+            return true
+        }
+
+        if self.generation + 1 < self.replay.generations.len() {
+            self.load(self.generation + 1);
+            return true
+        }
+
+        false
+    }
+
+    fn back(&mut self) {
+
+        if self.iteration > 0 {
+            self.iteration -= 1;
+
+        } else if self.generation > 0 {
+            self.load(self.generation - 1);
+            self.iteration = self.frames.len() - 1;
+        }
+    }
+
+    /// Draw the current frame and the status line
+
+    fn draw(&self) -> String {
+
+        let recorded = &self.replay.generations[self.generation];
+
+        let mut status = format!(
+            "Generation {} ({}/{}){} | {} | {} ms",
+            recorded.generation,
+            self.generation + 1,
+            self.replay.generations.len(),
+            if recorded.converged { " converged" } else { "" },
+            if self.paused { "paused" } else { "playing" },
+            self.frame_delay,
+        );
+
+        match &self.input {
+            Some(input) => status.push_str(&format!("\nGo to generation: {}_", input)),
+            None => status.push_str(&format!("\n{}", HELP)),
+        }
+
+        format!("{}{}\n", self.frames[self.iteration].render(), status)
+    }
+}
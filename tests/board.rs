@@ -0,0 +1,125 @@
+#![allow(clippy::empty_line_after_doc_comments)]
+
+use genetic::entity::Movement;
+use genetic::topology::Topology;
+use genetic::board::{Board, Boundary};
+use genetic::{ConfigError, Point, SimulationConfig};
+
+/// Check if `to` is a neighbour of `from` in the topology, the
+/// top and bottom rows are joined with the wrap boundary
+
+fn is_neighbour(topology: Topology, from: Point, to: Point, rows: isize, wrap: bool) -> bool {
+
+    let dx = to.x - from.x;
+
+    let dy = match to.y - from.y {
+        dy if wrap && dy == rows - 1 => -1,
+        dy if wrap && dy == 1 - rows => 1,
+        dy => dy,
+    };
+
+    match topology {
+        Topology::VonNeumann => dx.abs() + dy.abs() == 1,
+        Topology::Moore => dx.abs() <= 1 && dy.abs() <= 1 && (dx, dy) != (0, 0),
+        Topology::MooreStay => dx.abs() <= 1 && dy.abs() <= 1,
+
+        // Las filas impares están desplazadas media celda a la derecha
+
+        Topology::Hex => match (dy, from.y.rem_euclid(2) == 0) {
+            (0, _) => dx.abs() == 1,
+            (-1 | 1, true) => dx == -1 || dx == 0,
+            (-1 | 1, false) => dx == 0 || dx == 1,
+            _ => false,
+        },
+    }
+}
+
+/// Cells on the edges of the board, corners included
+
+fn edge_cells(rows: isize, cols: isize) -> Vec<Point> {
+    (0..rows)
+        .flat_map(|y| (0..cols).map(move |x| Point::new(x, y)))
+        .filter(|p| p.x == 0 || p.y == 0 || p.x == cols - 1 || p.y == rows - 1)
+        .collect()
+}
+
+#[test]
+fn edge_moves_reach_neighbours() {
+
+    for topology in Topology::ALL {
+        for boundary in [Boundary::Blocked, Boundary::Wrap, Boundary::Reflect, Boundary::Lethal] {
+
+            // Con un número impar de filas hay filas impares en los dos bordes horizontales
+
+            for dimensions in [(12, 20), (11, 20)] {
+
+                let config = SimulationConfig { dimensions, topology, boundary, ..SimulationConfig::default() };
+
+                if config.validate().is_err() {
+                    continue
+                }
+
+                let board = Board::new(&config);
+                let (rows, cols) = (dimensions.0 as isize, dimensions.1 as isize);
+
+                for from in edge_cells(rows, cols) {
+                    for index in 0..topology.genes() {
+
+                        let next = from + topology.direction(from, index);
+                        let inside = board.contains(next);
+                        let case = format!("{} {:?} {:?} from ({}, {}) {}", topology, boundary, dimensions, from.x, from.y, topology.names()[index]);
+
+                        match board.destination(from, index) {
+                            Movement::To(to) => {
+                                assert!(board.contains(to), "{}: ({}, {}) is outside", case, to.x, to.y);
+                                assert!(is_neighbour(topology, from, to, rows, boundary == Boundary::Wrap), "{}: ({}, {}) isn't a neighbour", case, to.x, to.y);
+                            },
+                            Movement::Blocked => {
+
+                                // Sin paredes solo bloquea el borde, con wrap solo los lados
+
+                                let sideways = !(0..cols).contains(&next.x);
+                                assert!(!inside && (boundary == Boundary::Blocked || boundary == Boundary::Wrap && sideways), "{}: blocked", case);
+                            },
+                            Movement::Fall => assert!(!inside && boundary == Boundary::Lethal, "{}: fell", case),
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn hex_reflect_on_odd_row() {
+
+    let config = SimulationConfig { dimensions: (11, 20), topology: Topology::Hex, boundary: Boundary::Reflect, ..SimulationConfig::default() };
+    let board = Board::new(&config);
+
+    let names = Topology::Hex.names();
+    let index = |name: &str| names.iter().position(|n| *n == name).unwrap();
+
+    // La fila 1 está desplazada, desde el borde derecho arriba-derecha rebota como arriba-izquierda
+
+    let from = Point::new(19, 1);
+
+    assert_eq!(board.destination(from, index("right")), Movement::To(Point::new(18, 1)));
+    assert_eq!(board.destination(from, index("up-right")), Movement::To(Point::new(19, 0)));
+    assert_eq!(board.destination(from, index("down-right")), Movement::To(Point::new(19, 2)));
+    assert_eq!(board.destination(from, index("up-left")), Movement::To(Point::new(19, 0)));
+}
+
+#[test]
+fn hex_wrap_needs_even_rows() {
+
+    let config = SimulationConfig { dimensions: (11, 20), topology: Topology::Hex, boundary: Boundary::Wrap, ..SimulationConfig::default() };
+    assert_eq!(config.validate(), Err(ConfigError::HexWrap(11)));
+
+    // La última fila es impar y la primera par, siguen siendo vecinas
+
+    let config = SimulationConfig { dimensions: (12, 20), ..config };
+    let board = Board::new(&config);
+
+    let down_right = Topology::Hex.names().iter().position(|n| *n == "down-right").unwrap();
+    assert_eq!(board.destination(Point::new(5, 11), down_right), Movement::To(Point::new(6, 0)));
+}
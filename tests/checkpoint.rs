@@ -0,0 +1,74 @@
+use std::io;
+use std::fs;
+use std::env;
+
+use genetic::{Point, SimulationBuilder};
+use genetic::genome::HallOfFame;
+use genetic::checkpoint::Checkpoint;
+
+#[test]
+fn resumed_run_matches_uninterrupted_one() {
+
+    let builder = SimulationBuilder::new().seed(3).headless(true);
+
+    let mut uninterrupted = HallOfFame::new(10);
+    let mut poblation = builder.clone().build().unwrap();
+
+    let converged = poblation.run_with(&mut uninterrupted);
+
+    // La misma simulación se interrumpe tras 20 generaciones y sigue desde el archivo
+
+    let mut hall_of_fame = HallOfFame::new(10);
+    let mut interrupted = builder.build().unwrap();
+
+    for _ in 0..20 {
+        assert!(!interrupted.step_generation_with(&mut hall_of_fame));
+    }
+
+    let path = env::temp_dir().join(format!("genetic-checkpoint-{}.json", std::process::id()));
+
+    let mut checkpoint = Checkpoint::capture(&interrupted);
+    checkpoint.hall_of_fame = Some(hall_of_fame);
+    checkpoint.save(&path).unwrap();
+
+    let mut checkpoint = Checkpoint::load(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    let mut hall_of_fame = checkpoint.hall_of_fame.take().unwrap();
+    let mut resumed = checkpoint.restore();
+
+    assert_eq!(resumed.actual_gen, 21);
+    assert_eq!(resumed.run_with(&mut hall_of_fame), converged);
+
+    assert_eq!(resumed.generations, poblation.generations);
+    assert_eq!(resumed.entities, poblation.entities);
+    assert_eq!(hall_of_fame.genomes, uninterrupted.genomes);
+}
+
+#[test]
+fn load_rejects_invalid_entities() {
+
+    let poblation = SimulationBuilder::new().seed(3).headless(true).build().unwrap();
+
+    let edits: [fn(&mut Checkpoint); 5] = [
+        |c| c.entities.truncate(3),
+        |c| c.entities[0].position = None,
+        |c| c.entities[1].position = c.entities[0].position,
+        |c| c.entities[0].position = Some(Point::new(10, 3)),
+        |c| { c.entities[0].values.pop(); },
+    ];
+
+    for (index, edit) in edits.iter().enumerate() {
+
+        let path = env::temp_dir().join(format!("genetic-invalid-{}-{}.json", std::process::id(), index));
+
+        let mut checkpoint = Checkpoint::capture(&poblation);
+        edit(&mut checkpoint);
+        checkpoint.save(&path).unwrap();
+
+        let error = Checkpoint::load(&path).unwrap_err();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData, "edit {}", index);
+    }
+}
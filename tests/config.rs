@@ -0,0 +1,37 @@
+use genetic::board::Goal;
+use genetic::genome::HallOfFame;
+use genetic::{ConfigError, Point, SimulationBuilder, SimulationConfig};
+
+#[test]
+fn goals_cant_cover_start_cells() {
+
+    let goal = Goal { area: Some([Point::new(0, 0), Point::new(1, 11)]), ..Goal::default() };
+    let error = SimulationBuilder::new().goals(vec![goal]).build().unwrap_err();
+
+    assert_eq!(error, ConfigError::GoalOnStart(Point::new(0, 0)));
+
+    let goal = Goal { area: Some([Point::new(17, 0), Point::new(19, 1)]), weight: 2.0, ..Goal::default() };
+
+    assert!(SimulationBuilder::new().goals(vec![goal]).seed(1).build().is_ok());
+}
+
+#[test]
+fn genomes_are_recorded_in_the_config() {
+
+    let mut hall_of_fame = HallOfFame::new(4);
+    SimulationBuilder::new().seed(3).headless(true).build().unwrap().run_with(&mut hall_of_fame);
+
+    let mut poblation = SimulationBuilder::new().seed(9).genomes(hall_of_fame.genomes.clone()).headless(true).build().unwrap();
+
+    assert_eq!(poblation.config.genomes, hall_of_fame.genomes);
+
+    // La configuración escrita basta para repetir la ejecución
+
+    let config: SimulationConfig = toml::from_str(&poblation.config.to_toml()).unwrap();
+    let mut repeated = SimulationBuilder::new().config(config).build().unwrap();
+
+    poblation.run();
+    repeated.run();
+
+    assert_eq!(repeated.generations, poblation.generations);
+}
@@ -0,0 +1,40 @@
+use genetic::map::{self, Map};
+use genetic::{ConfigError, Point, SimulationBuilder};
+
+#[test]
+fn parse_reads_every_cell() {
+
+    let map = Map::parse("\n  SS.#G  \n  S..#G\n\n").unwrap();
+
+    assert_eq!(map.dimensions, (2, 5));
+    assert_eq!(map.walls, vec![Point::new(3, 0), Point::new(3, 1)]);
+    assert_eq!(map.starts, vec![Point::new(0, 0), Point::new(1, 0), Point::new(0, 1)]);
+    assert_eq!(map.goals, vec![Point::new(4, 0), Point::new(4, 1)]);
+}
+
+#[test]
+fn parse_rejects_invalid_maps() {
+
+    let error = |text: &str| match Map::parse(text) {
+        Err(ConfigError::Map(message)) => message,
+        other => panic!("expected a map error, got {:?}", other),
+    };
+
+    assert_eq!(error(" \n\n"), "the map is empty");
+    assert_eq!(error("SS..G\nSS.G"), "line 2 has 4 cells, the first row has 5");
+    assert_eq!(error("\nSS.xG"), "line 2 has an unknown cell 'x'");
+}
+
+#[test]
+fn bundled_maps_are_valid() {
+
+    for (name, text) in map::BUNDLED {
+        assert!(Map::parse(text).is_ok(), "{} can't be parsed", name);
+        assert!(SimulationBuilder::new().map(text).seed(1).build().is_ok(), "{} isn't a valid board", name);
+    }
+}
+
+#[test]
+fn map_boards_can_be_smaller_than_the_default_minimum() {
+    assert!(SimulationBuilder::new().map("SSG").sample(2).seed(1).build().is_ok());
+}
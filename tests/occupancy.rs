@@ -0,0 +1,80 @@
+#![allow(clippy::empty_line_after_doc_comments)]
+
+use genetic::{Point, Poblation, SimulationBuilder};
+use genetic::board::Boundary;
+
+/// Check `entity_at` on every cell against a scan of the entities
+
+fn assert_in_sync(poblation: &Poblation) {
+
+    let (rows, cols) = poblation.board.dimensions;
+
+    for y in 0..rows as isize {
+        for x in 0..cols as isize {
+
+            let point = Point::new(x, y);
+            let expected = poblation.entities.iter().find(|e| e.alive && e.position == Some(point));
+
+            assert_eq!(poblation.entity_at(point).map(|e| e.id), expected.map(|e| e.id), "cell ({}, {})", x, y);
+        }
+    }
+}
+
+#[test]
+fn index_follows_moves_murders_and_falls() {
+
+    let mut poblation = SimulationBuilder::new()
+        .seed(5)
+        .killer_probability(0.5)
+        .boundary(Boundary::Lethal)
+        .generations(3)
+        .headless(true)
+        .build()
+        .unwrap();
+
+    assert_in_sync(&poblation);
+
+    for _ in 0..3 {
+
+        while !poblation.step_iteration() {
+            assert_in_sync(&poblation);
+        }
+
+        if poblation.finish_generation() {
+            break
+        }
+
+        assert_in_sync(&poblation);
+    }
+}
+
+#[test]
+fn index_is_rebuilt_after_editing_entities() {
+
+    let mut poblation = SimulationBuilder::new().seed(5).headless(true).build().unwrap();
+
+    poblation.step_iteration();
+
+    // Se mata a una entidad, se mueve otra y se quita la última
+
+    let moved = Point::new(5, 5);
+
+    poblation.entities[0].alive = false;
+    poblation.entities[1].position = Some(moved);
+    poblation.entities.pop();
+
+    poblation.reindex();
+    assert_in_sync(&poblation);
+
+    assert_eq!(poblation.entity_at(moved).map(|e| e.id), Some(poblation.entities[1].id));
+
+    poblation.entities.truncate(2);
+
+    // Sin reindex las entradas viejas no devuelven entidades equivocadas
+
+    assert_in_sync(&poblation);
+
+    while !poblation.step_iteration() {
+        assert_in_sync(&poblation);
+    }
+}
@@ -0,0 +1,19 @@
+use genetic::SimulationBuilder;
+
+#[test]
+fn converged_generation_is_recorded_once() {
+
+    let mut poblation = SimulationBuilder::new().seed(3).headless(true).build().unwrap();
+
+    assert!(poblation.run());
+
+    let generations = poblation.generations.clone();
+    let actual_gen = poblation.actual_gen;
+
+    assert!(poblation.step_generation());
+    assert!(poblation.finish_generation());
+    assert!(poblation.step_iteration());
+
+    assert_eq!(poblation.generations, generations);
+    assert_eq!(poblation.actual_gen, actual_gen);
+}
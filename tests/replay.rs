@@ -0,0 +1,34 @@
+use std::io;
+use std::fs;
+use std::env;
+
+use genetic::SimulationBuilder;
+use genetic::replay::{Replay, ReplayGenerations, ReplayRecorder};
+
+#[test]
+fn load_rejects_steps_of_unknown_entities() {
+
+    let mut poblation = SimulationBuilder::new().seed(3).generations(2).headless(true).build().unwrap();
+    let mut recorder = ReplayRecorder::new(&poblation.config, ReplayGenerations::All);
+
+    poblation.run_with(&mut recorder);
+
+    let replay = recorder.finish();
+    let path = env::temp_dir().join(format!("genetic-replay-{}.rpl", std::process::id()));
+
+    replay.save(&path).unwrap();
+    assert!(Replay::load(&path).is_ok());
+
+    let mut invalid = replay.clone();
+    invalid.generations[0].iterations[0].moves.push((999, 1, 1));
+    invalid.save(&path).unwrap();
+    assert_eq!(Replay::load(&path).unwrap_err().kind(), io::ErrorKind::InvalidData);
+
+    let mut invalid = replay;
+    let count = invalid.generations[0].entities.len();
+    invalid.generations[0].iterations[0].deaths.push(count);
+    invalid.save(&path).unwrap();
+    assert_eq!(Replay::load(&path).unwrap_err().kind(), io::ErrorKind::InvalidData);
+
+    fs::remove_file(&path).unwrap();
+}